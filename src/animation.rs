@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use crate::Sprite;
+
+/* The frames of an animation, all in a single list
+ * They are usually cut out of one large sprite, laid out in a grid
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpriteSheet {
+    frames: Vec<Sprite>,
+}
+
+impl SpriteSheet {
+    // Create a sheet out of separate frames
+    pub fn from_frames(frames: Vec<Sprite>) -> SpriteSheet {
+        return SpriteSheet { frames };
+    }
+
+    /* Slice a sprite into frames of the given size
+     * Frames are taken from left to right and from top to bottom,
+     * and frames that don't fit whole at the right or bottom edge are left out
+    */
+    pub fn from_grid(image: &Sprite, frame_width: i32, frame_height: i32) -> SpriteSheet {
+        let mut frames = Vec::new();
+
+        if frame_width > 0 && frame_height > 0 {
+            for row in 0..image.height() / frame_height {
+                for column in 0..image.width() / frame_width {
+                    frames.push(image.crop(column * frame_width, row * frame_height, frame_width, frame_height));
+                }
+            }
+        }
+
+        return SpriteSheet { frames };
+    }
+
+    // Number of frames in the sheet
+    pub fn len(&self) -> usize {
+        return self.frames.len();
+    }
+
+    // Check if the sheet has no frames
+    pub fn is_empty(&self) -> bool {
+        return self.frames.is_empty();
+    }
+
+    // Get a frame by its index, None if there is no such frame
+    pub fn frame(&self, index: usize) -> Option<&Sprite> {
+        return self.frames.get(index);
+    }
+
+    // All of the frames, in order
+    pub fn frames(&self) -> &[Sprite] {
+        return &self.frames;
+    }
+}
+
+// What an animation does after its last frame
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationMode {
+    // Stop at the last frame
+    Once,
+    // Start over from the first frame
+    #[default]
+    Loop,
+    // Play the frames backwards down to the first one, then forwards again
+    PingPong,
+}
+
+/* A sequence of frames of a sprite sheet, each one shown for some time
+ *
+ * The animation doesn't keep track of time on its own, it's advanced by
+ * calling update with the time that passed since the last update,
+ * usually once per frame of the program.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Animation {
+    // Index in the sprite sheet and how long it's shown
+    frames: Vec<(usize, Duration)>,
+    mode: AnimationMode,
+    elapsed: Duration,
+}
+
+impl Animation {
+    // Create an animation showing every frame of the list for the same time
+    pub fn new(frames: &[usize], duration: Duration, mode: AnimationMode) -> Animation {
+        return Animation::with_durations(frames.iter().map(|frame| (*frame, duration)).collect(), mode);
+    }
+
+    // Create an animation from frames of the sheet and how long each one is shown
+    pub fn with_durations(frames: Vec<(usize, Duration)>, mode: AnimationMode) -> Animation {
+        return Animation {
+            frames,
+            mode,
+            elapsed: Duration::ZERO,
+        };
+    }
+
+    // What the animation does after its last frame
+    pub fn mode(&self) -> AnimationMode {
+        return self.mode;
+    }
+
+    // Time the animation has been playing
+    pub fn elapsed(&self) -> Duration {
+        return self.elapsed;
+    }
+
+    // Advance the animation by the time that passed since the last update
+    pub fn update(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    // Play the animation again from the start
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    // Check if an animation played once has reached its end
+    pub fn is_finished(&self) -> bool {
+        return self.mode == AnimationMode::Once && self.elapsed >= self.length();
+    }
+
+    /* Index in the sprite sheet of the frame shown right now
+     * Returns None if the animation has no frames
+    */
+    pub fn current_frame(&self) -> Option<usize> {
+        let timeline = self.timeline();
+        let length: Duration = timeline.iter().map(|(_, duration)| *duration).sum();
+
+        let mut time = match self.mode {
+            _ if length.is_zero() => Duration::ZERO,
+            AnimationMode::Once if self.elapsed >= length => return timeline.last().map(|(frame, _)| *frame),
+            AnimationMode::Once => self.elapsed,
+            AnimationMode::Loop | AnimationMode::PingPong => {
+                Duration::from_nanos((self.elapsed.as_nanos() % length.as_nanos()) as u64)
+            },
+        };
+
+        for (frame, duration) in &timeline {
+            if time < *duration {
+                return Some(*frame);
+            }
+            time -= *duration;
+        }
+
+        return timeline.first().map(|(frame, _)| *frame);
+    }
+
+    // The sprite of the sheet shown right now
+    pub fn current_sprite<'a>(&self, sheet: &'a SpriteSheet) -> Option<&'a Sprite> {
+        return sheet.frame(self.current_frame()?);
+    }
+}
+
+// Implement private methods for Animation
+impl Animation {
+    // Time it takes to play every frame once
+    fn length(&self) -> Duration {
+        return self.frames.iter().map(|(_, duration)| *duration).sum();
+    }
+
+    /* Frames in the order they are played during one cycle
+     * Ping pong goes back without repeating the first and last frames
+    */
+    fn timeline(&self) -> Vec<(usize, Duration)> {
+        let mut timeline = self.frames.clone();
+
+        if self.mode == AnimationMode::PingPong && self.frames.len() > 2 {
+            timeline.extend(self.frames[1..self.frames.len() - 1].iter().rev());
+        }
+
+        return timeline;
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt::Write as _;
+use std::io::{self, Stdout, Write};
+
+use super::Backend;
+use crate::{terminal, Cell, ColorDepth};
+
+/* Backend that encodes the cells as ANSI escape sequences into any writer
+ *
+ * Everything is kept in memory until flush, so a whole frame reaches
+ * the writer in a single write. Cursor movements are skipped for
+ * consecutive cells and the style is only switched when it changes.
+ *
+ * Colours are written as they are unless a lower colour depth is set.
+*/
+pub struct WriterBackend<W: Write> {
+    writer: W,
+    width: i32,
+    height: i32,
+    depth: ColorDepth,
+
+    buffer: String,
+    cursor: Option<(i32, i32)>,     // None while the cursor position is unknown
+    style: Option<Cell>,            // None while the active style is unknown
+}
+
+impl<W: Write> WriterBackend<W> {
+    // Create a backend that reports the given size
+    pub fn new(writer: W, width: i32, height: i32) -> WriterBackend<W> {
+        return WriterBackend {
+            writer,
+            width,
+            height,
+            depth: ColorDepth::TrueColor,
+
+            buffer: String::new(),
+            cursor: None,
+            style: None,
+        };
+    }
+
+    // Return the backend downgrading colours to the given depth
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> WriterBackend<W> {
+        self.depth = depth;
+        return self;
+    }
+
+    // The colour depth the output is limited to
+    pub fn color_depth(&self) -> ColorDepth {
+        return self.depth;
+    }
+
+    // Get a reference to the underlying writer
+    pub fn get_ref(&self) -> &W {
+        return &self.writer;
+    }
+
+    // Get a mutable reference to the underlying writer
+    pub fn get_mut(&mut self) -> &mut W {
+        return &mut self.writer;
+    }
+
+    // Unwrap the underlying writer, dropping anything that wasn't flushed
+    pub fn into_inner(self) -> W {
+        return self.writer;
+    }
+}
+
+impl<W: Write> Backend for WriterBackend<W> {
+    fn draw(&mut self, cells: &[(i32, i32, &Cell)]) -> io::Result<()> {
+        for (x, y, cell) in cells {
+            // The terminal already filled it when the wide glyph was written
+            if cell.is_continuation() {
+                continue;
+            }
+
+            if self.cursor != Some((*x, *y)) {
+                self.move_cursor(*x, *y)?;
+            }
+
+            if !self.style.as_ref().is_some_and(|style| style.same_style(cell)) {
+                self.buffer.push_str(&cell.sgr(self.depth));
+                self.style = Some((*cell).clone());
+            }
+
+            self.buffer.push_str(&cell.glyph);
+            self.cursor = Some((x + cell.width().max(1) as i32, *y));
+        }
+
+        return Ok(());
+    }
+
+    fn move_cursor(&mut self, x: i32, y: i32) -> io::Result<()> {
+        // Terminal coordinates start at 1
+        let _ = write!(self.buffer, "\x1b[{};{}H", y + 1, x + 1);
+        self.cursor = Some((x, y));
+        return Ok(());
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.buffer.push_str("\x1b[0m\x1b[2J");
+        self.style = Some(Cell::default());
+        return Ok(());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Don't leave the terminal with the style of the last cell
+        if self.style.as_ref().is_some_and(|style| !style.same_style(&Cell::default())) {
+            self.buffer.push_str("\x1b[0m");
+            self.style = Some(Cell::default());
+        }
+
+        self.writer.write_all(self.buffer.as_bytes())?;
+        self.buffer.clear();
+        return self.writer.flush();
+    }
+
+    fn size(&self) -> io::Result<(i32, i32)> {
+        return Ok((self.width, self.height));
+    }
+}
+
+/* Backend that draws on the terminal through the standard output
+ * Colours are limited to the depth detected from the environment
+*/
+pub struct StdoutBackend {
+    inner: WriterBackend<Stdout>,
+}
+
+impl StdoutBackend {
+    pub fn new() -> StdoutBackend {
+        // The size is only used until the terminal can be asked again
+        let (width, height) = terminal::size().unwrap_or((80, 24));
+
+        return StdoutBackend {
+            inner: WriterBackend::new(io::stdout(), width, height).with_color_depth(ColorDepth::detect()),
+        };
+    }
+
+    // The colour depth the output is limited to
+    pub fn color_depth(&self) -> ColorDepth {
+        return self.inner.color_depth();
+    }
+
+    // Limit the output to a different colour depth than the detected one
+    pub fn set_color_depth(&mut self, depth: ColorDepth) {
+        self.inner.depth = depth;
+    }
+}
+
+impl Default for StdoutBackend {
+    fn default() -> StdoutBackend {
+        return StdoutBackend::new();
+    }
+}
+
+impl Backend for StdoutBackend {
+    fn draw(&mut self, cells: &[(i32, i32, &Cell)]) -> io::Result<()> {
+        return self.inner.draw(cells);
+    }
+
+    fn move_cursor(&mut self, x: i32, y: i32) -> io::Result<()> {
+        return self.inner.move_cursor(x, y);
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        return self.inner.clear();
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.inner.flush();
+    }
+
+    fn size(&self) -> io::Result<(i32, i32)> {
+        return terminal::size();
+    }
+
+    fn watches_signals(&self) -> bool {
+        return true;
+    }
+}
@@ -0,0 +1,131 @@
+/* Encoding of cells for the legacy Windows console API
+ *
+ * Old consoles can't interpret escape sequences, so every cell has to be
+ * written with WriteConsoleOutputW as a UTF-16 character and a set of
+ * attribute bits. Nothing here calls into Windows, so it's available
+ * (and testable) on every platform.
+*/
+
+use crate::{Cell, Color, ColorDepth};
+
+// Attribute bits of the console, the background uses the same bits shifted by 4
+pub const FOREGROUND_BLUE: u16 = 0x0001;
+pub const FOREGROUND_GREEN: u16 = 0x0002;
+pub const FOREGROUND_RED: u16 = 0x0004;
+pub const FOREGROUND_INTENSITY: u16 = 0x0008;
+pub const BACKGROUND_SHIFT: u16 = 4;
+pub const COMMON_LVB_UNDERSCORE: u16 = 0x8000;
+
+// Character written in place of glyphs that don't fit in a single UTF-16 unit
+pub const REPLACEMENT: u16 = '?' as u16;
+
+// A row of consecutive cells, ready to be written in a single call
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsoleRun {
+    pub x: i32,
+    pub y: i32,
+    // Each cell as (UTF-16 character, attributes)
+    pub chars: Vec<(u16, u16)>,
+}
+
+/* Attribute bits of a colour for the foreground,
+ * shift them by BACKGROUND_SHIFT for the background
+ *
+ * Colours are reduced to the 16 colour palette first.
+ * Returns None for the default colour.
+*/
+pub fn color_bits(color: Color) -> Option<u16> {
+    let index = match color.downgrade(ColorDepth::Ansi16) {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::White => 7,
+        Color::BrightBlack => 8,
+        Color::BrightRed => 9,
+        Color::BrightGreen => 10,
+        Color::BrightYellow => 11,
+        Color::BrightBlue => 12,
+        Color::BrightMagenta => 13,
+        Color::BrightCyan => 14,
+        Color::BrightWhite => 15,
+        _ => return None,
+    };
+
+    // ANSI orders the bits as red, green, blue while the console uses blue, green, red
+    let mut bits = 0;
+    if index & 1 != 0 {
+        bits |= FOREGROUND_RED;
+    }
+    if index & 2 != 0 {
+        bits |= FOREGROUND_GREEN;
+    }
+    if index & 4 != 0 {
+        bits |= FOREGROUND_BLUE;
+    }
+    if index & 8 != 0 {
+        bits |= FOREGROUND_INTENSITY;
+    }
+
+    return Some(bits);
+}
+
+/* Attribute bits of a cell
+ * `default` holds the attributes used for the default colours,
+ * usually the ones the console had when the program started
+*/
+pub fn cell_attributes(cell: &Cell, default: u16) -> u16 {
+    let mut fg = color_bits(cell.fg).unwrap_or(default & 0x000F);
+    let mut bg = color_bits(cell.bg).unwrap_or((default & 0x00F0) >> BACKGROUND_SHIFT);
+
+    // There is no bold font, so it is shown as the bright variant of the colour
+    if cell.attrs.bold {
+        fg |= FOREGROUND_INTENSITY;
+    }
+    if cell.attrs.reverse {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+
+    let mut attributes = fg | (bg << BACKGROUND_SHIFT);
+    if cell.attrs.underline {
+        attributes |= COMMON_LVB_UNDERSCORE;
+    }
+
+    return attributes;
+}
+
+// UTF-16 unit of a glyph, or REPLACEMENT if it needs more than one
+pub fn glyph_unit(glyph: &str) -> u16 {
+    let mut units = glyph.encode_utf16();
+
+    return match (units.next(), units.next()) {
+        (Some(unit), None) => unit,
+        _ => REPLACEMENT,
+    };
+}
+
+/* Group the cells into runs of consecutive cells of the same row
+ * Cells with negative coordinates can't be written and are dropped,
+ * just like the right halves of wide glyphs, which the console fills itself
+*/
+pub fn console_runs(cells: &[(i32, i32, &Cell)], default: u16) -> Vec<ConsoleRun> {
+    let mut runs: Vec<ConsoleRun> = Vec::new();
+
+    for (x, y, cell) in cells {
+        if *x < 0 || *y < 0 || cell.is_continuation() {
+            continue;
+        }
+
+        let encoded = (glyph_unit(&cell.glyph), cell_attributes(cell, default));
+
+        match runs.last_mut() {
+            Some(run) if run.y == *y && run.x + run.chars.len() as i32 == *x => run.chars.push(encoded),
+            _ => runs.push(ConsoleRun { x: *x, y: *y, chars: vec![encoded] }),
+        }
+    }
+
+    return runs;
+}
@@ -0,0 +1,149 @@
+use std::io;
+
+use super::Backend;
+use crate::{rect, Cell};
+
+/* Backend that keeps the drawn cells in memory instead of showing them
+ * Useful to inspect the output of BsCmdGraph, for example in tests
+*/
+pub struct HeadlessBackend {
+    width: i32,
+    height: i32,
+    cells: Vec<Cell>,
+    cursor: (i32, i32),
+}
+
+impl HeadlessBackend {
+    /* Create an empty backend of the given size
+     * Panics if the backend has more cells than an i32 can count
+    */
+    pub fn new(width: i32, height: i32) -> HeadlessBackend {
+        let size = rect::area(width, height).unwrap_or_else(|error| panic!("{}", error));
+
+        return HeadlessBackend {
+            width: width.max(0),
+            height: height.max(0),
+            cells: vec![Cell::default(); size],
+            cursor: (0, 0),
+        };
+    }
+
+    /* Change the size of the backend, like a terminal window being resized
+     * The cells that still fit are kept. Panics if the size is too large, like new
+    */
+    pub fn resize(&mut self, width: i32, height: i32) {
+        let size = rect::area(width, height).unwrap_or_else(|error| panic!("{}", error));
+        let width = width.max(0);
+        let height = height.max(0);
+        let mut cells = vec![Cell::default(); size];
+
+        for y in 0..height.min(self.height) {
+            for x in 0..width.min(self.width) {
+                cells[(y * width + x) as usize] = self.cells[(y * self.width + x) as usize].clone();
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+    }
+
+    // All the cells of the backend, row by row
+    pub fn cells(&self) -> &[Cell] {
+        return &self.cells;
+    }
+
+    // The cell at (x, y), or None if it's outside of the backend
+    pub fn cell(&self, x: i32, y: i32) -> Option<&Cell> {
+        if x < 0 || x >= self.width || y < 0 || y >= self.height {
+            return None;
+        }
+
+        return self.cells.get((y * self.width + x) as usize);
+    }
+
+    // Current position of the cursor
+    pub fn cursor(&self) -> (i32, i32) {
+        return self.cursor;
+    }
+
+    /* The glyphs of every row as plain text
+     * Wide glyphs appear once, taking the room of their continuation cell
+    */
+    pub fn lines(&self) -> Vec<String> {
+        return self.rows().map(|row| row.iter().map(|cell| cell.glyph.as_str()).collect()).collect();
+    }
+
+    /* The style of every cell as a grid of keys, along with what each key means
+     * Cells in the default style are shown as spaces, the rest of the styles
+     * get a letter in the order they first appear (the glyph of the styles is ignored)
+    */
+    pub fn style_map(&self) -> (Vec<String>, Vec<(char, Cell)>) {
+        let keys: Vec<char> = ('a'..='z').chain('A'..='Z').chain('0'..='9').collect();
+        let mut legend: Vec<(char, Cell)> = Vec::new();
+        let mut map = Vec::new();
+
+        for row in self.rows() {
+            let mut line = String::new();
+
+            for cell in row {
+                if cell.same_style(&Cell::default()) {
+                    line.push(' ');
+                    continue;
+                }
+
+                let key = match legend.iter().find(|(_, style)| style.same_style(cell)) {
+                    Some((key, _)) => *key,
+                    None => {
+                        // Styles beyond the available keys share the last one
+                        let key = keys[legend.len().min(keys.len() - 1)];
+                        legend.push((key, Cell::default().with_fg(cell.fg).with_bg(cell.bg).with_attrs(cell.attrs)));
+                        key
+                    },
+                };
+                line.push(key);
+            }
+
+            map.push(line);
+        }
+
+        return (map, legend);
+    }
+
+    // Iterate over the rows of cells
+    fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        return self.cells.chunks(self.width.max(1) as usize);
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn draw(&mut self, cells: &[(i32, i32, &Cell)]) -> io::Result<()> {
+        for (x, y, cell) in cells {
+            // Cells outside of the backend are dropped, like a terminal would
+            if *x >= 0 && *x < self.width && *y >= 0 && *y < self.height {
+                self.cells[(y * self.width + x) as usize] = (*cell).clone();
+            }
+            self.cursor = (x + 1, *y);
+        }
+
+        return Ok(());
+    }
+
+    fn move_cursor(&mut self, x: i32, y: i32) -> io::Result<()> {
+        self.cursor = (x, y);
+        return Ok(());
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.cells.fill(Cell::default());
+        return Ok(());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return Ok(());
+    }
+
+    fn size(&self) -> io::Result<(i32, i32)> {
+        return Ok((self.width, self.height));
+    }
+}
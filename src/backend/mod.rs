@@ -0,0 +1,50 @@
+use std::io;
+
+use crate::Cell;
+
+mod ansi;
+pub mod console;
+mod headless;
+#[cfg(windows)]
+mod windows;
+
+pub use ansi::{StdoutBackend, WriterBackend};
+pub use headless::HeadlessBackend;
+#[cfg(windows)]
+pub use self::windows::WindowsBackend;
+
+// Backend used by BsCmdGraph::new on this platform
+#[cfg(windows)]
+pub type DefaultBackend = WindowsBackend;
+#[cfg(not(windows))]
+pub type DefaultBackend = StdoutBackend;
+
+/* Destination of the frames drawn by BsCmdGraph
+ *
+ * Coordinates are given in cells, starting at (0, 0) in the top left corner.
+ * Backends may buffer what they are given until flush is called.
+*/
+pub trait Backend {
+    // Write each cell at its (x, y) position
+    fn draw(&mut self, cells: &[(i32, i32, &Cell)]) -> io::Result<()>;
+
+    // Place the cursor at (x, y)
+    fn move_cursor(&mut self, x: i32, y: i32) -> io::Result<()>;
+
+    // Clear the whole output, leaving only empty cells
+    fn clear(&mut self) -> io::Result<()>;
+
+    // Make everything written so far visible
+    fn flush(&mut self) -> io::Result<()>;
+
+    // Return the size of the output as (width, height)
+    fn size(&self) -> io::Result<(i32, i32)>;
+
+    /* Check if the size of the output is the size of the terminal, which
+     * announces its resizes with a signal. Other backends are asked for
+     * their size every time a display that follows it checks for resizes
+    */
+    fn watches_signals(&self) -> bool {
+        return false;
+    }
+}
@@ -0,0 +1,200 @@
+use std::io::{self, Stdout};
+
+use ::windows::Win32::Foundation::HANDLE;
+use ::windows::Win32::System::Console::{
+    self, CHAR_INFO, CHAR_INFO_0, CONSOLE_CHARACTER_ATTRIBUTES, CONSOLE_MODE, CONSOLE_SCREEN_BUFFER_INFO, COORD,
+    SMALL_RECT,
+};
+
+use super::console::{self, ConsoleRun};
+use super::{Backend, WriterBackend};
+use crate::{BsGraphError, Cell, ColorDepth, Result};
+
+// How the cells reach the console
+enum Output {
+    // Escape sequences, for consoles with virtual terminal processing
+    VirtualTerminal(WriterBackend<Stdout>),
+
+    // Direct writes to the screen buffer, for older consoles
+    Legacy { runs: Vec<ConsoleRun>, cursor: Option<(i32, i32)> },
+}
+
+/* Backend that draws on the Windows console
+ *
+ * Virtual terminal processing is enabled when the console supports it,
+ * so the frames are written as escape sequences like on any other terminal.
+ * Older consoles get the cells written straight into the screen buffer.
+ * The console is put back the way it was found when the backend is dropped.
+*/
+pub struct WindowsBackend {
+    handle: HANDLE,
+    original_mode: CONSOLE_MODE,
+    default_attributes: u16,
+    output: Output,
+}
+
+impl WindowsBackend {
+    // Create a backend for the console of the standard output
+    pub fn new() -> Result<WindowsBackend> {
+        let handle = unsafe { Console::GetStdHandle(Console::STD_OUTPUT_HANDLE) }
+            .map_err(|error| BsGraphError::TerminalUnavailable(error.to_string()))?;
+
+        let mut original_mode = CONSOLE_MODE::default();
+        if !unsafe { Console::GetConsoleMode(handle, &mut original_mode) }.as_bool() {
+            return Err(BsGraphError::TerminalUnavailable("standard output is not a console".to_owned()));
+        }
+
+        let info = screen_buffer_info(handle).map_err(|error| BsGraphError::TerminalUnavailable(error.to_string()))?;
+        let (width, height) = window_size(&info);
+
+        let virtual_terminal = original_mode | Console::ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+        let output = if unsafe { Console::SetConsoleMode(handle, virtual_terminal) }.as_bool() {
+            let writer = WriterBackend::new(io::stdout(), width, height).with_color_depth(ColorDepth::detect());
+            Output::VirtualTerminal(writer)
+        } else {
+            Output::Legacy { runs: Vec::new(), cursor: None }
+        };
+
+        return Ok(WindowsBackend {
+            handle,
+            original_mode,
+            default_attributes: info.wAttributes.0,
+            output,
+        });
+    }
+
+    // Check if the console interprets escape sequences
+    pub fn is_virtual_terminal(&self) -> bool {
+        return matches!(self.output, Output::VirtualTerminal(_));
+    }
+}
+
+impl Backend for WindowsBackend {
+    fn draw(&mut self, cells: &[(i32, i32, &Cell)]) -> io::Result<()> {
+        match &mut self.output {
+            Output::VirtualTerminal(writer) => return writer.draw(cells),
+            Output::Legacy { runs, .. } => {
+                runs.extend(console::console_runs(cells, self.default_attributes));
+                return Ok(());
+            },
+        }
+    }
+
+    fn move_cursor(&mut self, x: i32, y: i32) -> io::Result<()> {
+        match &mut self.output {
+            Output::VirtualTerminal(writer) => return writer.move_cursor(x, y),
+            Output::Legacy { cursor, .. } => {
+                *cursor = Some((x, y));
+                return Ok(());
+            },
+        }
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        if let Output::VirtualTerminal(writer) = &mut self.output {
+            return writer.clear();
+        }
+
+        // Fill the whole screen buffer with blanks in the default colours
+        let info = screen_buffer_info(self.handle)?;
+        let length = info.dwSize.X as u32 * info.dwSize.Y as u32;
+        let origin = COORD { X: 0, Y: 0 };
+        let mut written = 0;
+
+        unsafe {
+            Console::SetConsoleTextAttribute(self.handle, CONSOLE_CHARACTER_ATTRIBUTES(self.default_attributes));
+            check(Console::FillConsoleOutputCharacterW(self.handle, ' ' as u16, length, origin, &mut written))?;
+            check(Console::FillConsoleOutputAttribute(self.handle, self.default_attributes, length, origin, &mut written))?;
+        }
+
+        return Ok(());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let (runs, cursor) = match &mut self.output {
+            Output::VirtualTerminal(writer) => return writer.flush(),
+            Output::Legacy { runs, cursor } => (runs, cursor),
+        };
+
+        // Coordinates are relative to the visible window of the screen buffer
+        let info = screen_buffer_info(self.handle)?;
+        let (left, top) = (info.srWindow.Left as i32, info.srWindow.Top as i32);
+
+        for run in runs.drain(..) {
+            let buffer: Vec<CHAR_INFO> = run
+                .chars
+                .iter()
+                .map(|(unit, attributes)| CHAR_INFO {
+                    Char: CHAR_INFO_0 { UnicodeChar: *unit },
+                    Attributes: *attributes,
+                })
+                .collect();
+
+            let x = (left + run.x) as i16;
+            let y = (top + run.y) as i16;
+            let mut region = SMALL_RECT {
+                Left: x,
+                Top: y,
+                Right: x + buffer.len() as i16 - 1,
+                Bottom: y,
+            };
+
+            unsafe {
+                check(Console::WriteConsoleOutputW(
+                    self.handle,
+                    buffer.as_ptr(),
+                    COORD { X: buffer.len() as i16, Y: 1 },
+                    COORD { X: 0, Y: 0 },
+                    &mut region,
+                ))?;
+            }
+        }
+
+        if let Some((x, y)) = cursor.take() {
+            let position = COORD { X: (left + x) as i16, Y: (top + y) as i16 };
+            unsafe {
+                check(Console::SetConsoleCursorPosition(self.handle, position))?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn size(&self) -> io::Result<(i32, i32)> {
+        return Ok(window_size(&screen_buffer_info(self.handle)?));
+    }
+}
+
+impl Drop for WindowsBackend {
+    fn drop(&mut self) {
+        unsafe {
+            Console::SetConsoleMode(self.handle, self.original_mode);
+            Console::SetConsoleTextAttribute(self.handle, CONSOLE_CHARACTER_ATTRIBUTES(self.default_attributes));
+        }
+    }
+}
+
+// Read the current state of the screen buffer
+fn screen_buffer_info(handle: HANDLE) -> io::Result<CONSOLE_SCREEN_BUFFER_INFO> {
+    let mut info = CONSOLE_SCREEN_BUFFER_INFO::default();
+    unsafe {
+        check(Console::GetConsoleScreenBufferInfo(handle, &mut info))?;
+    }
+
+    return Ok(info);
+}
+
+// Size of the visible window of the screen buffer as (width, height)
+fn window_size(info: &CONSOLE_SCREEN_BUFFER_INFO) -> (i32, i32) {
+    let window = info.srWindow;
+    return ((window.Right - window.Left + 1) as i32, (window.Bottom - window.Top + 1) as i32);
+}
+
+// Turn the result of a console function into an io::Result
+fn check(result: ::windows::Win32::Foundation::BOOL) -> io::Result<()> {
+    if !result.as_bool() {
+        return Err(io::Error::last_os_error());
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,418 @@
+/* Bitmap fonts
+ *
+ * Every glyph of a bitmap font is a small monochrome image, which is drawn
+ * on the display scaled up to any size, e.g. for the digits of a clock.
+ * Fonts can be loaded from BDF files, which are text, and from the PSF
+ * files of the Linux console, versions 1 and 2.
+ *
+ * All the glyphs of a font are stored as tall as the font, aligned to the
+ * same baseline, so a line of text is just the glyphs one after another.
+*/
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{BsGraphError, Result, Style};
+
+// Magic numbers at the start of PSF files
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+
+// Largest size or offset of a glyph in pixels, far more than any real font needs
+const MAX_GLYPH_SIZE: i32 = 256;
+
+// Bundled font
+const BUILTIN: &str = include_str!("../fonts/5x7.bdf");
+
+// A glyph of a bitmap font, as rows of pixels from the top
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitmapGlyph {
+    width: usize,
+    rows: Vec<Vec<bool>>,
+}
+
+impl BitmapGlyph {
+    // Number of columns the glyph takes, including the space after it
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    // Number of rows of the glyph, the same as the font
+    pub fn height(&self) -> usize {
+        return self.rows.len();
+    }
+
+    // Check if the pixel at (x, y) is set, pixels outside of the glyph never are
+    pub fn pixel(&self, x: usize, y: usize) -> bool {
+        return self.rows.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false);
+    }
+}
+
+// A monochrome bitmap font
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    height: usize,
+    glyphs: HashMap<char, BitmapGlyph>,
+    default: Option<char>,      // Drawn in place of missing characters
+}
+
+// How the pixels of bitmap text are turned into cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitmapMode {
+    // Every pixel takes a whole cell filled with the character
+    Fill(char),
+    // Every cell holds two pixels, one above the other, drawn with half blocks
+    HalfBlock,
+}
+
+/* How bitmap text is drawn
+ * Every pixel of the font becomes a square of `scale` by `scale` pixels
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitmapOptions {
+    pub scale: i32,
+    pub mode: BitmapMode,
+    pub style: Style,
+}
+
+impl Default for BitmapOptions {
+    fn default() -> BitmapOptions {
+        return BitmapOptions {
+            scale: 1,
+            mode: BitmapMode::HalfBlock,
+            style: Style::default(),
+        };
+    }
+}
+
+impl BitmapOptions {
+    // Half block pixels at their own size in the default colours
+    pub fn new() -> BitmapOptions {
+        return BitmapOptions::default();
+    }
+
+    // Return a copy of the options with a different scale
+    pub fn with_scale(mut self, scale: i32) -> BitmapOptions {
+        self.scale = scale;
+        return self;
+    }
+
+    // Return a copy of the options with a different mode
+    pub fn with_mode(mut self, mode: BitmapMode) -> BitmapOptions {
+        self.mode = mode;
+        return self;
+    }
+
+    // Return a copy of the options with a different style
+    pub fn with_style(mut self, style: Style) -> BitmapOptions {
+        self.style = style;
+        return self;
+    }
+}
+
+impl BitmapFont {
+    /* Parse a font from the contents of a BDF file
+     * Fails with InvalidFont if the file isn't a valid BDF font
+    */
+    pub fn parse_bdf(source: &str) -> Result<BitmapFont> {
+        let mut lines = source.lines().map(str::trim);
+        if !lines.next().is_some_and(|line| line.starts_with("STARTFONT")) {
+            return Err(invalid("missing STARTFONT"));
+        }
+
+        // Bounding box of the whole font as (width, height, x offset, y offset)
+        let mut bounds: Option<[i32; 4]> = None;
+        let mut ascent = None;
+        let mut descent = None;
+        let mut default = None;
+        let mut chars = Vec::new();
+
+        while let Some(line) = lines.next() {
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+            match keyword {
+                "FONTBOUNDINGBOX" => bounds = Some(numbers(rest)?),
+                "FONT_ASCENT" => ascent = Some(number(rest)?),
+                "FONT_DESCENT" => descent = Some(number(rest)?),
+                "DEFAULT_CHAR" => default = u32::try_from(number(rest)?).ok().and_then(char::from_u32),
+                "STARTCHAR" => chars.push(read_bdf_char(&mut lines)?),
+                "ENDFONT" => break,
+                _ => {},
+            }
+        }
+
+        // Sizes are kept small enough that adding a few of them can't overflow
+        let [width, height, _, y_offset] = glyph_sizes(bounds.ok_or_else(|| invalid("missing FONTBOUNDINGBOX"))?)?;
+        let [ascent, descent] = glyph_sizes([ascent.unwrap_or(height + y_offset), descent.unwrap_or(-y_offset)])?;
+        if ascent + descent <= 0 {
+            return Err(invalid("the font has no height"));
+        }
+
+        let mut font = BitmapFont {
+            height: (ascent + descent) as usize,
+            glyphs: HashMap::new(),
+            default,
+        };
+
+        for bdf_char in chars {
+            // Glyphs outside of Unicode have an encoding of -1
+            let Some(character) = u32::try_from(bdf_char.encoding).ok().and_then(char::from_u32) else {
+                continue;
+            };
+
+            let [glyph_width, glyph_height, x_offset, y_offset] = glyph_sizes(bdf_char.bounds.unwrap_or([width, height, 0, y_offset]))?;
+            let [advance] = glyph_sizes([bdf_char.advance.unwrap_or(width)])?;
+            let columns = advance.max(x_offset.max(0) + glyph_width).max(0) as usize;
+            let mut rows = vec![vec![false; columns]; font.height];
+
+            // The bottom of the glyph is y_offset pixels above the baseline
+            let top = ascent - (y_offset + glyph_height);
+            for (r, bits) in bdf_char.bitmap.iter().enumerate().take(glyph_height.max(0) as usize) {
+                let y = top + r as i32;
+                if y < 0 || y >= font.height as i32 {
+                    continue;
+                }
+
+                for c in 0..glyph_width.max(0) {
+                    let x = x_offset + c;
+                    if x >= 0 && bits.get(c as usize / 8).is_some_and(|byte| byte & (0x80 >> (c % 8)) != 0) {
+                        rows[y as usize][x as usize] = true;
+                    }
+                }
+            }
+
+            font.glyphs.insert(character, BitmapGlyph { width: columns, rows });
+        }
+
+        return Ok(font);
+    }
+
+    /* Parse a font from the contents of a PSF file, version 1 or 2
+     *
+     * Glyphs are mapped to characters by the Unicode table of the font,
+     * or by their position if it has none.
+     * Fails with InvalidFont if the file isn't a valid PSF font
+    */
+    pub fn parse_psf(bytes: &[u8]) -> Result<BitmapFont> {
+        let (count, height, width, glyph_size, header_size, unicode) = if bytes.starts_with(&PSF2_MAGIC) {
+            let field = |i: usize| -> Result<usize> {
+                let field = bytes.get(i * 4..i * 4 + 4).ok_or_else(|| invalid("the header is too short"))?;
+                return Ok(u32::from_le_bytes(field.try_into().unwrap()) as usize);
+            };
+
+            // magic, version, header size, flags, length, glyph size, height, width
+            (field(4)?, field(6)?, field(7)?, field(5)?, field(2)?, field(3)? & 1 != 0)
+        } else if bytes.starts_with(&PSF1_MAGIC) && bytes.len() >= 4 {
+            // magic, mode, glyph size
+            let mode = bytes[2];
+            let count = if mode & 0x01 != 0 { 512 } else { 256 };
+            (count, bytes[3] as usize, 8, bytes[3] as usize, 4, mode & 0x06 != 0)
+        } else {
+            return Err(invalid("missing the PSF magic number"));
+        };
+
+        let limit = MAX_GLYPH_SIZE as usize;
+        if width == 0 || height == 0 || width > limit || height > limit {
+            return Err(invalid("a glyph size or offset is out of range"));
+        }
+
+        let row_size = width.div_ceil(8);
+        if glyph_size < row_size * height {
+            return Err(invalid("the glyphs are too small for their size"));
+        }
+
+        let table_start = count.checked_mul(glyph_size).and_then(|size| size.checked_add(header_size));
+        let table_start = table_start.ok_or_else(|| invalid("the font ends before its glyphs"))?;
+        let bitmaps = bytes.get(header_size..table_start).ok_or_else(|| invalid("the font ends before its glyphs"))?;
+
+        let glyphs: Vec<BitmapGlyph> = bitmaps
+            .chunks(glyph_size)
+            .map(|bitmap| {
+                let rows = (0..height)
+                    .map(|y| (0..width).map(|x| bitmap[y * row_size + x / 8] & (0x80 >> (x % 8)) != 0).collect())
+                    .collect();
+                BitmapGlyph { width, rows }
+            })
+            .collect();
+
+        let mut font = BitmapFont {
+            height,
+            glyphs: HashMap::new(),
+            default: None,
+        };
+
+        if !unicode {
+            for (i, glyph) in glyphs.into_iter().enumerate() {
+                if let Some(character) = char::from_u32(i as u32) {
+                    font.glyphs.insert(character, glyph);
+                }
+            }
+            return Ok(font);
+        }
+
+        // Every glyph lists its characters, followed by sequences that are ignored
+        let table = &bytes[table_start..];
+        let entries = if bytes.starts_with(&PSF2_MAGIC) {
+            psf2_table(table)
+        } else {
+            psf1_table(table)
+        };
+
+        for (glyph, characters) in glyphs.iter().zip(entries) {
+            for character in characters {
+                font.glyphs.insert(character, glyph.clone());
+            }
+        }
+
+        return Ok(font);
+    }
+
+    /* Load a font from a BDF or PSF file, told apart by their contents
+     * Fails with Io if it can't be read, or InvalidFont if it isn't a valid font
+    */
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<BitmapFont> {
+        let bytes = std::fs::read(path).map_err(BsGraphError::Io)?;
+
+        if bytes.starts_with(&PSF1_MAGIC) || bytes.starts_with(&PSF2_MAGIC) {
+            return BitmapFont::parse_psf(&bytes);
+        }
+
+        let source = String::from_utf8(bytes).map_err(|_| invalid("a BDF font must be text"))?;
+        return BitmapFont::parse_bdf(&source);
+    }
+
+    // Bundled font with glyphs of 5 by 7 pixels, covering printable ASCII
+    pub fn builtin() -> BitmapFont {
+        return BitmapFont::parse_bdf(BUILTIN).expect("the bundled bitmap font is valid");
+    }
+
+    // Number of rows of pixels of every line of text
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    /* Get the glyph of a character
+     * Characters the font doesn't have get its default glyph, if it has one
+    */
+    pub fn glyph(&self, character: char) -> Option<&BitmapGlyph> {
+        return self.glyphs.get(&character).or_else(|| self.default.and_then(|default| self.glyphs.get(&default)));
+    }
+
+    /* Render text into rows of pixels
+     * Every line of the text takes as many rows as the font is tall,
+     * and all the rows are as wide as the longest line
+    */
+    pub fn render(&self, text: &str) -> Vec<Vec<bool>> {
+        let mut rows: Vec<Vec<bool>> = Vec::new();
+
+        for line in text.lines() {
+            let mut line_rows = vec![Vec::new(); self.height];
+
+            for glyph in line.chars().filter_map(|character| self.glyph(character)) {
+                for (row, glyph_row) in line_rows.iter_mut().zip(&glyph.rows) {
+                    row.extend(glyph_row);
+                }
+            }
+
+            rows.extend(line_rows);
+        }
+
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, false);
+        }
+
+        return rows;
+    }
+}
+
+// A character of a BDF font, before it's placed in the font
+struct BdfChar {
+    encoding: i32,
+    advance: Option<i32>,
+    bounds: Option<[i32; 4]>,
+    bitmap: Vec<Vec<u8>>,
+}
+
+// Read a BDF character, right after its STARTCHAR line
+fn read_bdf_char<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<BdfChar> {
+    let mut bdf_char = BdfChar {
+        encoding: -1,
+        advance: None,
+        bounds: None,
+        bitmap: Vec::new(),
+    };
+    let mut in_bitmap = false;
+
+    for line in lines.by_ref() {
+        let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+        match keyword {
+            "ENDCHAR" => return Ok(bdf_char),
+            "ENCODING" => bdf_char.encoding = number(rest.split_whitespace().next().unwrap_or_default())?,
+            "DWIDTH" => bdf_char.advance = Some(number(rest.split_whitespace().next().unwrap_or_default())?),
+            "BBX" => bdf_char.bounds = Some(numbers(rest)?),
+            "BITMAP" => in_bitmap = true,
+            hex if in_bitmap => {
+                // Every byte is two hexadecimal digits, so the row can be split anywhere
+                if hex.len() % 2 != 0 || !hex.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+                    return Err(invalid("a bitmap row isn't hexadecimal"));
+                }
+
+                let bytes = hex.as_bytes().chunks(2).map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap()).collect();
+                bdf_char.bitmap.push(bytes);
+            },
+            _ => {},
+        }
+    }
+
+    return Err(invalid("the font ends in the middle of a character"));
+}
+
+// Characters of every glyph in a PSF1 Unicode table, as UTF-16 units ended by 0xFFFF
+fn psf1_table(table: &[u8]) -> Vec<Vec<char>> {
+    let units: Vec<u16> = table.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]])).collect();
+
+    return units
+        .split(|unit| *unit == 0xFFFF)
+        .map(|entry| {
+            // Sequences of several characters start at 0xFFFE
+            let single = entry.split(|unit| *unit == 0xFFFE).next().unwrap_or_default();
+            single.iter().filter_map(|unit| char::from_u32(*unit as u32)).collect()
+        })
+        .collect();
+}
+
+// Characters of every glyph in a PSF2 Unicode table, as UTF-8 ended by 0xFF
+fn psf2_table(table: &[u8]) -> Vec<Vec<char>> {
+    return table
+        .split(|byte| *byte == 0xFF)
+        .map(|entry| {
+            // Sequences of several characters start at 0xFE
+            let single = entry.split(|byte| *byte == 0xFE).next().unwrap_or_default();
+            String::from_utf8_lossy(single).chars().filter(|c| *c != char::REPLACEMENT_CHARACTER).collect()
+        })
+        .collect();
+}
+
+fn number(text: &str) -> Result<i32> {
+    return text.trim().parse::<i32>().map_err(|_| invalid(&format!("{:?} isn't a number", text)));
+}
+
+fn numbers<const N: usize>(text: &str) -> Result<[i32; N]> {
+    let values = text.split_whitespace().map(number).collect::<Result<Vec<i32>>>()?;
+    return values.get(..N).and_then(|values| values.try_into().ok()).ok_or_else(|| invalid(&format!("expected {} numbers", N)));
+}
+
+// Check that sizes and offsets of glyphs are within MAX_GLYPH_SIZE
+fn glyph_sizes<const N: usize>(values: [i32; N]) -> Result<[i32; N]> {
+    if values.iter().any(|value| value.unsigned_abs() > MAX_GLYPH_SIZE as u32) {
+        return Err(invalid("a glyph size or offset is out of range"));
+    }
+
+    return Ok(values);
+}
+
+fn invalid(reason: &str) -> BsGraphError {
+    return BsGraphError::InvalidFont(reason.to_owned());
+}
@@ -0,0 +1,79 @@
+/* Borders of boxes
+ *
+ * A box is a rectangle outlined with box-drawing characters, and it can
+ * have a title written over its top border. Every border style is a set
+ * of the characters for the four corners and the two kinds of edges.
+*/
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Characters the border of a box is drawn with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BorderStyle {
+    // Plain ASCII, for terminals without box-drawing characters: +-+
+    Ascii,
+    // Thin lines: ┌─┐
+    #[default]
+    Single,
+    // Double lines: ╔═╗
+    Double,
+    // Thick lines: ┏━┓
+    Heavy,
+    // Thin lines with round corners: ╭─╮
+    Rounded,
+}
+
+impl BorderStyle {
+    /* Characters of the border, in this order: top left, top right,
+     * bottom left and bottom right corners, horizontal and vertical edges
+    */
+    pub fn glyphs(self) -> [char; 6] {
+        return match self {
+            BorderStyle::Ascii => ['+', '+', '+', '+', '-', '|'],
+            BorderStyle::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            BorderStyle::Heavy => ['┏', '┓', '┗', '┛', '━', '┃'],
+            BorderStyle::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+        };
+    }
+
+    /* Character of the border at (x, y), which has to be on the edge of the rectangle
+     * Boxes one cell high or wide are just a horizontal or vertical line
+    */
+    pub(crate) fn glyph_at(self, x: i32, y: i32, width: i32, height: i32) -> char {
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = self.glyphs();
+
+        if height == 1 {
+            return horizontal;
+        }
+        if width == 1 {
+            return vertical;
+        }
+
+        return match (x == 0, x == width - 1, y == 0, y == height - 1) {
+            (true, _, true, _) => top_left,
+            (_, true, true, _) => top_right,
+            (true, _, _, true) => bottom_left,
+            (_, true, _, true) => bottom_right,
+            (_, _, true, _) | (_, _, _, true) => horizontal,
+            _ => vertical,
+        };
+    }
+}
+
+/* The longest start of a title that fits in the given number of columns
+ * Titles are cut between characters, and never halfway through a wide one
+*/
+pub(crate) fn fit_title(title: &str, columns: i32) -> &str {
+    let mut width = 0;
+
+    for (index, grapheme) in title.grapheme_indices(true) {
+        width += grapheme.width() as i32;
+        if width > columns {
+            return &title[..index];
+        }
+    }
+
+    return title;
+}
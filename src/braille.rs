@@ -0,0 +1,183 @@
+/* Braille canvas
+ *
+ * Every braille character (U+2800 to U+28FF) is a grid of 2 by 4 dots,
+ * any of which can be raised. A braille canvas uses them as pixels, so a
+ * cell of the display holds 8 of them and lines look much smoother than
+ * with whole cells. Every cell has one colour, the last one it was drawn in.
+*/
+
+use crate::{raster, rect, BsGraphError, Color, Result};
+
+// Code point of the braille character without any dots
+pub(crate) const BRAILLE_BLANK: u32 = 0x2800;
+
+// Bits of the dots of a braille character, by column and row
+pub(crate) const BRAILLE_DOTS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+// A grid of dots, drawn on the display as braille characters
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BrailleCanvas {
+    columns: i32,
+    rows: i32,
+
+    // Row-major buffers of columns * rows cells
+    dots: Vec<u8>,
+    colors: Vec<Color>,
+}
+
+impl BrailleCanvas {
+    /* Create an empty canvas of the given size in cells
+     * Panics if the canvas has more dots than an i32 can count
+    */
+    pub fn new(columns: i32, rows: i32) -> BrailleCanvas {
+        // Sized by the dots, which have to fit in the coordinates too
+        let dots = columns.checked_mul(2).zip(rows.checked_mul(4)).map(|(width, height)| rect::area(width, height));
+        let Some(Ok(_)) = dots else {
+            panic!("{}", BsGraphError::InvalidSize { width: columns, height: rows });
+        };
+        let size = (columns.max(0) * rows.max(0)) as usize;
+
+        return BrailleCanvas {
+            columns: columns.max(0),
+            rows: rows.max(0),
+            dots: vec![0; size],
+            colors: vec![Color::Default; size],
+        };
+    }
+
+    // Width of the canvas in cells
+    pub fn columns(&self) -> i32 {
+        return self.columns;
+    }
+
+    // Height of the canvas in cells
+    pub fn rows(&self) -> i32 {
+        return self.rows;
+    }
+
+    // Width of the canvas in dots, two per cell
+    pub fn width(&self) -> i32 {
+        return self.columns * 2;
+    }
+
+    // Height of the canvas in dots, four per cell
+    pub fn height(&self) -> i32 {
+        return self.rows * 4;
+    }
+
+    // Lower every dot of the canvas
+    pub fn clear(&mut self) {
+        self.dots.fill(0);
+        self.colors.fill(Color::Default);
+    }
+
+    /* Raise the dot at (x, y) and give its cell the colour
+     * Returns true if the dot is within the canvas, false otherwise
+    */
+    pub fn put_pixel(&mut self, x: i32, y: i32, color: Color) -> bool {
+        return self.try_put_pixel(x, y, color).is_ok();
+    }
+
+    /* Raise the dot at (x, y) and give its cell the colour
+     * Fails with OutOfBounds if the dot is outside of the canvas
+    */
+    pub fn try_put_pixel(&mut self, x: i32, y: i32, color: Color) -> Result<()> {
+        let (index, bit) = self.locate(x, y).ok_or(BsGraphError::OutOfBounds { x, y })?;
+
+        self.dots[index] |= bit;
+        self.colors[index] = color;
+        return Ok(());
+    }
+
+    /* Lower the dot at (x, y)
+     * Returns true if the dot is within the canvas, false otherwise
+    */
+    pub fn unset_pixel(&mut self, x: i32, y: i32) -> bool {
+        let Some((index, bit)) = self.locate(x, y) else {
+            return false;
+        };
+
+        self.dots[index] &= !bit;
+        return true;
+    }
+
+    // Check if the dot at (x, y) is raised, dots outside of the canvas never are
+    pub fn get_pixel(&self, x: i32, y: i32) -> bool {
+        return self.locate(x, y).is_some_and(|(index, bit)| self.dots[index] & bit != 0);
+    }
+
+    /* Draw a line of dots between two points
+     * Returns true if the whole line was drawn, false if some of it was clipped
+    */
+    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: Color) -> bool {
+        return self.try_draw_line(x1, y1, x2, y2, color).is_ok();
+    }
+
+    /* Draw a line of dots between two points
+     * Fails with OutOfBounds on the first dot that was clipped
+    */
+    pub fn try_draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: Color) -> Result<()> {
+        let mut result = Ok(());
+
+        raster::line(x1, y1, x2, y2, |x, y| {
+            let drawn = self.try_put_pixel(x, y, color);
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
+
+        return result;
+    }
+
+    /* Draw a polygon of dots, represented as a vertex vector
+     * Returns true if the whole polygon was drawn, false if some of it was clipped
+    */
+    pub fn draw_poly(&mut self, vertex_array: Vec<[i32; 2]>, color: Color) -> bool {
+        return self.try_draw_poly(vertex_array, color).is_ok();
+    }
+
+    /* Draw a polygon of dots, represented as a vertex vector
+     * Fails with OutOfBounds on the first dot that was clipped
+    */
+    pub fn try_draw_poly(&mut self, vertex_array: Vec<[i32; 2]>, color: Color) -> Result<()> {
+        let mut result = Ok(());
+
+        raster::poly(&vertex_array, false, |x, y| {
+            let drawn = self.try_put_pixel(x, y, color);
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
+
+        return result;
+    }
+
+    /* The braille character and colour of the cell at (column, row)
+     * Returns None for cells without any raised dots, or outside of the canvas
+    */
+    pub fn cell(&self, column: i32, row: i32) -> Option<(char, Color)> {
+        if column < 0 || column >= self.columns || row < 0 || row >= self.rows {
+            return None;
+        }
+
+        let index = (row * self.columns + column) as usize;
+        if self.dots[index] == 0 {
+            return None;
+        }
+
+        return Some((char::from_u32(BRAILLE_BLANK + self.dots[index] as u32).unwrap(), self.colors[index]));
+    }
+}
+
+// Implement private methods for BrailleCanvas
+impl BrailleCanvas {
+    // Index of the cell holding the dot at (x, y) and the bit of the dot in it
+    fn locate(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        if x < 0 || x >= self.width() || y < 0 || y >= self.height() {
+            return None;
+        }
+
+        let index = ((y / 4) * self.columns + x / 2) as usize;
+        return Some((index, BRAILLE_DOTS[(x % 2) as usize][(y % 4) as usize]));
+    }
+}
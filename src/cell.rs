@@ -0,0 +1,272 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::{Color, ColorDepth};
+
+// Text attributes that can be applied to a cell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Attributes {
+    pub bold: bool,
+    pub underline: bool,
+    pub italic: bool,
+    pub reverse: bool,
+}
+
+/* Colours and attributes of a cell, without the glyph
+ * Used to draw text, where every character becomes a cell of its own
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub fg: Color,
+    pub bg: Color,
+    pub attrs: Attributes,
+}
+
+impl Style {
+    // The default colours without any attributes
+    pub fn new() -> Style {
+        return Style::default();
+    }
+
+    // Return a copy of the style with a different foreground colour
+    pub fn with_fg(mut self, fg: Color) -> Style {
+        self.fg = fg;
+        return self;
+    }
+
+    // Return a copy of the style with a different background colour
+    pub fn with_bg(mut self, bg: Color) -> Style {
+        self.bg = bg;
+        return self;
+    }
+
+    // Return a copy of the style with different attributes
+    pub fn with_attrs(mut self, attrs: Attributes) -> Style {
+        self.attrs = attrs;
+        return self;
+    }
+
+    // Return a copy of the style in bold
+    pub fn bold(mut self) -> Style {
+        self.attrs.bold = true;
+        return self;
+    }
+
+    // Return a copy of the style underlined
+    pub fn underline(mut self) -> Style {
+        self.attrs.underline = true;
+        return self;
+    }
+
+    // Return a copy of the style in italics
+    pub fn italic(mut self) -> Style {
+        self.attrs.italic = true;
+        return self;
+    }
+
+    // Return a copy of the style with swapped foreground and background
+    pub fn reverse(mut self) -> Style {
+        self.attrs.reverse = true;
+        return self;
+    }
+}
+
+/* A single character cell of the display
+ *
+ * The glyph is a whole grapheme cluster, so accented letters and emoji
+ * made of several code points fit in one cell. Wide glyphs take two
+ * columns; the cell to their right is then a continuation cell with
+ * an empty glyph, which is never drawn on its own.
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub glyph: String,
+    pub fg: Color,
+    pub bg: Color,
+    pub attrs: Attributes,
+}
+
+impl Default for Cell {
+    // An empty cell is a blank space in the default colours
+    fn default() -> Cell {
+        return Cell {
+            glyph: String::from(" "),
+            fg: Color::Default,
+            bg: Color::Default,
+            attrs: Attributes::default(),
+        };
+    }
+}
+
+impl Cell {
+    // Create a cell with the given glyph in the default colours
+    pub fn new(glyph: char) -> Cell {
+        return Cell {
+            glyph: glyph.to_string(),
+            ..Cell::default()
+        };
+    }
+
+    // Create a cell holding a grapheme cluster in the default colours
+    pub fn grapheme(cluster: &str) -> Cell {
+        return Cell {
+            glyph: cluster.to_owned(),
+            ..Cell::default()
+        };
+    }
+
+    // The right half of a wide glyph
+    pub(crate) fn continuation(&self) -> Cell {
+        return Cell {
+            glyph: String::new(),
+            ..self.clone()
+        };
+    }
+
+    // Check if this cell is the right half of a wide glyph
+    pub fn is_continuation(&self) -> bool {
+        return self.glyph.is_empty();
+    }
+
+    // Number of columns the glyph takes on the terminal
+    pub fn width(&self) -> usize {
+        return self.glyph.width();
+    }
+
+    // Return a copy of the cell with a different foreground colour
+    pub fn with_fg(mut self, fg: Color) -> Cell {
+        self.fg = fg;
+        return self;
+    }
+
+    // Return a copy of the cell with a different background colour
+    pub fn with_bg(mut self, bg: Color) -> Cell {
+        self.bg = bg;
+        return self;
+    }
+
+    // Return a copy of the cell with different attributes
+    pub fn with_attrs(mut self, attrs: Attributes) -> Cell {
+        self.attrs = attrs;
+        return self;
+    }
+
+    // Return a copy of the cell with the colours and attributes of a style
+    pub fn with_style(mut self, style: Style) -> Cell {
+        self.fg = style.fg;
+        self.bg = style.bg;
+        self.attrs = style.attrs;
+        return self;
+    }
+
+    // The colours and attributes of the cell
+    pub fn style(&self) -> Style {
+        return Style {
+            fg: self.fg,
+            bg: self.bg,
+            attrs: self.attrs,
+        };
+    }
+
+    // Return a copy of the cell drawn in bold
+    pub fn bold(mut self) -> Cell {
+        self.attrs.bold = true;
+        return self;
+    }
+
+    // Return a copy of the cell drawn underlined
+    pub fn underline(mut self) -> Cell {
+        self.attrs.underline = true;
+        return self;
+    }
+
+    // Return a copy of the cell drawn in italics
+    pub fn italic(mut self) -> Cell {
+        self.attrs.italic = true;
+        return self;
+    }
+
+    // Return a copy of the cell with swapped foreground and background
+    pub fn reverse(mut self) -> Cell {
+        self.attrs.reverse = true;
+        return self;
+    }
+
+    // A blank cell with the same style
+    pub(crate) fn blanked(&self) -> Cell {
+        return Cell {
+            glyph: String::from(" "),
+            ..self.clone()
+        };
+    }
+
+    // Check if both cells are drawn with the same colours and attributes
+    pub fn same_style(&self, other: &Cell) -> bool {
+        return self.fg == other.fg && self.bg == other.bg && self.attrs == other.attrs;
+    }
+
+    /* Build the escape sequence that selects the style of this cell
+     * The sequence always starts by resetting the previous style,
+     * colours are downgraded to what the terminal can show
+    */
+    pub(crate) fn sgr(&self, depth: ColorDepth) -> String {
+        let mut sequence = String::from("\x1b[0");
+
+        let fg = self.fg.downgrade(depth);
+        if fg != Color::Default {
+            sequence += &format!(";{}", fg.fg_sgr());
+        }
+        let bg = self.bg.downgrade(depth);
+        if bg != Color::Default {
+            sequence += &format!(";{}", bg.bg_sgr());
+        }
+        if self.attrs.bold {
+            sequence += ";1";
+        }
+        if self.attrs.italic {
+            sequence += ";3";
+        }
+        if self.attrs.underline {
+            sequence += ";4";
+        }
+        if self.attrs.reverse {
+            sequence += ";7";
+        }
+
+        sequence.push('m');
+        return sequence;
+    }
+}
+
+/* Write a cell into a row-major buffer of the given width, keeping wide glyphs whole
+ *
+ * Overwriting half of a wide glyph blanks its other half, and a wide glyph
+ * writes its own continuation cell. A wide glyph that doesn't fit before
+ * the right edge is replaced by a blank. The coordinates must be within the buffer.
+*/
+pub(crate) fn place(buffer: &mut [Cell], width: i32, x: i32, y: i32, cell: Cell) {
+    let index = (y * width + x) as usize;
+    let wide = cell.width() > 1;
+
+    if buffer[index].is_continuation() && x > 0 {
+        buffer[index - 1] = buffer[index - 1].blanked();
+    }
+
+    // Both cells covered by a wide glyph lose whatever started there
+    let last = if wide { x + 1 } else { x };
+    for covered in x..=last.min(width - 1) {
+        let covered_index = (y * width + covered) as usize;
+        if buffer[covered_index].width() > 1 && covered + 1 < width {
+            buffer[covered_index + 1] = buffer[covered_index + 1].blanked();
+        }
+    }
+
+    if wide && x + 1 >= width {
+        buffer[index] = cell.blanked();
+        return;
+    }
+
+    if wide {
+        buffer[index + 1] = cell.continuation();
+    }
+    buffer[index] = cell;
+}
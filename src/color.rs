@@ -0,0 +1,307 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::BsGraphError;
+
+/* Colour of the foreground or background of a cell
+ *
+ * Named colours are the 16 colours of the ANSI palette, whose exact look
+ * depends on the terminal. Indexed colours pick from the 256 colour palette
+ * and Rgb colours are 24-bit truecolor.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Color {
+    // The terminal's own colour
+    #[default]
+    Default,
+
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+// How many colours a terminal can show
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    // No colours at all, only the terminal's default
+    Monochrome,
+    // The 16 named colours
+    Ansi16,
+    // The 256 colour palette
+    Ansi256,
+    // Any 24-bit colour
+    TrueColor,
+}
+
+// The named colours in palette order
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+// What the named colours look like in xterm
+const NAMED_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+// Levels of each channel in the 6x6x6 colour cube of the 256 colour palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    // Position of a named colour in the palette, None for the rest
+    fn palette_index(self) -> Option<u8> {
+        return NAMED.iter().position(|named| *named == self).map(|index| index as u8);
+    }
+
+    /* The red, green and blue components of the colour, as xterm shows them
+     * Returns None for the default colour, which depends on the terminal
+    */
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        if let Some(index) = self.palette_index() {
+            return Some(NAMED_RGB[index as usize]);
+        }
+
+        return match self {
+            Color::Indexed(index) if index < 16 => Some(NAMED_RGB[index as usize]),
+            Color::Indexed(index) if index < 232 => {
+                let cube = index - 16;
+                Some((
+                    CUBE_LEVELS[(cube / 36) as usize],
+                    CUBE_LEVELS[(cube / 6 % 6) as usize],
+                    CUBE_LEVELS[(cube % 6) as usize],
+                ))
+            },
+            Color::Indexed(index) => {
+                let level = 8 + (index - 232) * 10;
+                Some((level, level, level))
+            },
+            Color::Rgb(r, g, b) => Some((r, g, b)),
+            _ => None,
+        };
+    }
+
+    /* Convert the colour to the closest one the given depth can show
+     * Colours that already fit are returned unchanged
+    */
+    pub fn downgrade(self, depth: ColorDepth) -> Color {
+        return match (self, depth) {
+            (Color::Default, _) => Color::Default,
+            (_, ColorDepth::Monochrome) => Color::Default,
+            (_, ColorDepth::TrueColor) => self,
+
+            (Color::Rgb(r, g, b), ColorDepth::Ansi256) => Color::Indexed(nearest_indexed(r, g, b)),
+            (Color::Indexed(index), ColorDepth::Ansi16) if index < 16 => NAMED[index as usize],
+            (Color::Indexed(_) | Color::Rgb(..), ColorDepth::Ansi16) => {
+                // The conversion is only None for the default colour, handled above
+                let (r, g, b) = self.to_rgb().unwrap_or_default();
+                NAMED[nearest(&NAMED_RGB, (r, g, b))]
+            },
+
+            // Named colours fit in every palette
+            _ => self,
+        };
+    }
+
+    // Parameters of the SGR sequence that selects this colour for the foreground
+    pub(crate) fn fg_sgr(self) -> String {
+        return self.sgr(30, 90, 38, 39);
+    }
+
+    // Parameters of the SGR sequence that selects this colour for the background
+    pub(crate) fn bg_sgr(self) -> String {
+        return self.sgr(40, 100, 48, 49);
+    }
+
+    fn sgr(self, base: u8, bright: u8, extended: u8, default: u8) -> String {
+        if let Some(index) = self.palette_index() {
+            if index < 8 {
+                return (base + index).to_string();
+            }
+            return (bright + index - 8).to_string();
+        }
+
+        return match self {
+            Color::Indexed(index) => format!("{};5;{}", extended, index),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", extended, r, g, b),
+            _ => default.to_string(),
+        };
+    }
+}
+
+impl fmt::Display for Color {
+    // Human readable name, like "red", "indexed 208" or "#ff8800"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Color::Default => write!(f, "default"),
+            Color::Indexed(index) => write!(f, "indexed {}", index),
+            Color::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            named => {
+                // Turn "BrightRed" into "bright red"
+                let name = format!("{:?}", named);
+                let mut words = String::new();
+                for (i, letter) in name.chars().enumerate() {
+                    if letter.is_uppercase() && i > 0 {
+                        words.push(' ');
+                    }
+                    words.push(letter.to_ascii_lowercase());
+                }
+                write!(f, "{}", words)
+            },
+        };
+    }
+}
+
+impl FromStr for Color {
+    type Err = BsGraphError;
+
+    /* Parse a colour from the name written by Display
+     * Words can also be joined by underscores or dashes, like "bright_red",
+     * and indexed colours can be written as a plain number
+    */
+    fn from_str(name: &str) -> Result<Color, BsGraphError> {
+        let words = name.trim().to_ascii_lowercase().replace(['_', '-'], " ");
+
+        if words == "default" {
+            return Ok(Color::Default);
+        }
+
+        if let Some(hex) = words.strip_prefix('#') {
+            let channel = |i: usize| hex.get(i..i + 2).and_then(|digits| u8::from_str_radix(digits, 16).ok());
+            if let (6, Some(r), Some(g), Some(b)) = (hex.len(), channel(0), channel(2), channel(4)) {
+                return Ok(Color::Rgb(r, g, b));
+            }
+        }
+
+        let index = words.strip_prefix("indexed ").unwrap_or(&words);
+        if let Ok(index) = index.trim().parse::<u8>() {
+            return Ok(Color::Indexed(index));
+        }
+
+        if let Some(named) = NAMED.iter().find(|named| named.to_string() == words) {
+            return Ok(*named);
+        }
+
+        return Err(BsGraphError::InvalidColor(name.to_owned()));
+    }
+}
+
+impl ColorDepth {
+    // Detect the depth of the current terminal from its environment variables
+    pub fn detect() -> ColorDepth {
+        // Honour https://no-color.org
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::Monochrome;
+        }
+
+        // Windows Terminal doesn't set COLORTERM but always supports truecolor
+        if std::env::var_os("WT_SESSION").is_some() {
+            return ColorDepth::TrueColor;
+        }
+
+        return ColorDepth::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        );
+    }
+
+    // Work out the depth from the values of COLORTERM and TERM
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+        if let Some("truecolor" | "24bit") = colorterm {
+            return ColorDepth::TrueColor;
+        }
+
+        return match term {
+            Some("dumb") => ColorDepth::Monochrome,
+            Some(term) if term.ends_with("-direct") => ColorDepth::TrueColor,
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        };
+    }
+}
+
+// Find the colour of the 256 colour palette (skipping the named ones) closest to an RGB colour
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    // Closest level of the cube for each channel
+    let level = |value: u8| nearest(&CUBE_LEVELS.map(|level| (level, level, level)), (value, value, value));
+    let (cube_r, cube_g, cube_b) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[cube_r], CUBE_LEVELS[cube_g], CUBE_LEVELS[cube_b]);
+
+    // Closest step of the grayscale ramp
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + step * 10;
+    let gray = (gray_level, gray_level, gray_level);
+
+    if distance(gray, (r, g, b)) < distance(cube, (r, g, b)) {
+        return 232 + step;
+    }
+
+    return 16 + (cube_r * 36 + cube_g * 6 + cube_b) as u8;
+}
+
+// Index of the colour in `palette` closest to `color`
+fn nearest(palette: &[(u8, u8, u8)], color: (u8, u8, u8)) -> usize {
+    let mut best = 0;
+
+    for (index, candidate) in palette.iter().enumerate() {
+        if distance(*candidate, color) < distance(palette[best], color) {
+            best = index;
+        }
+    }
+
+    return best;
+}
+
+// Squared distance between two colours
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+
+    return (dr * dr + dg * dg + db * db) as u32;
+}
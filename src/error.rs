@@ -0,0 +1,71 @@
+use std::fmt;
+use std::io;
+
+// Everything that can go wrong while drawing
+#[derive(Debug)]
+pub enum BsGraphError {
+    // The coordinates are outside of the display
+    OutOfBounds { x: i32, y: i32 },
+
+    // The code point can't be drawn as a character
+    InvalidGlyph(u32),
+
+    // A display or buffer has more cells than its coordinates can reach
+    InvalidSize { width: i32, height: i32 },
+
+    // A buffer doesn't have as many values as its size needs
+    SizeMismatch { expected: usize, actual: usize },
+
+    // The text doesn't name a colour
+    InvalidColor(String),
+
+    // The font file is malformed, with the reason why
+    InvalidFont(String),
+
+    // The image file is malformed or in an unsupported format, with the reason why
+    InvalidImage(String),
+
+    // A file couldn't be read or written
+    Io(io::Error),
+
+    // The backend failed to write the frame
+    BackendIo(io::Error),
+
+    // There is no terminal to draw on, e.g. when no console is attached
+    TerminalUnavailable(String),
+}
+
+// Result of the fallible drawing functions
+pub type Result<T> = std::result::Result<T, BsGraphError>;
+
+impl fmt::Display for BsGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            BsGraphError::OutOfBounds { x, y } => write!(f, "coordinates ({}, {}) are out of bounds", x, y),
+            BsGraphError::InvalidGlyph(code) => write!(f, "{:#x} is not a valid character", code),
+            BsGraphError::InvalidSize { width, height } => write!(f, "a size of {}x{} is too large", width, height),
+            BsGraphError::SizeMismatch { expected, actual } => write!(f, "expected {} values but got {}", expected, actual),
+            BsGraphError::InvalidColor(name) => write!(f, "{:?} is not a valid colour", name),
+            BsGraphError::InvalidFont(reason) => write!(f, "invalid font: {}", reason),
+            BsGraphError::InvalidImage(reason) => write!(f, "invalid image: {}", reason),
+            BsGraphError::Io(error) => write!(f, "{}", error),
+            BsGraphError::BackendIo(error) => write!(f, "backend failed: {}", error),
+            BsGraphError::TerminalUnavailable(reason) => write!(f, "terminal unavailable: {}", reason),
+        };
+    }
+}
+
+impl std::error::Error for BsGraphError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            BsGraphError::BackendIo(error) | BsGraphError::Io(error) => Some(error),
+            _ => None,
+        };
+    }
+}
+
+impl From<io::Error> for BsGraphError {
+    fn from(error: io::Error) -> BsGraphError {
+        return BsGraphError::BackendIo(error);
+    }
+}
@@ -0,0 +1,167 @@
+/* Exporting the display as an image
+ *
+ * Every cell becomes a block of pixels filled with its background colour,
+ * with its glyph drawn on top in the foreground colour using a bitmap
+ * font. Block characters and braille are drawn as the shapes
+ * they stand for instead, so pictures made of them look right.
+*/
+
+use crate::braille::{BRAILLE_BLANK, BRAILLE_DOTS};
+use crate::pixel::block_pixels;
+use crate::{rect, BitmapFont, BsGraphError, Cell, Color, Image, Result, Rgba};
+
+/* How the display is turned into an image
+ * The default colours of the terminal are stood in for by `foreground` and `background`
+*/
+#[derive(Clone, Debug)]
+pub struct ExportOptions {
+    pub cell_width: i32,
+    pub cell_height: i32,
+    pub font: BitmapFont,
+    pub foreground: (u8, u8, u8),
+    pub background: (u8, u8, u8),
+}
+
+impl Default for ExportOptions {
+    fn default() -> ExportOptions {
+        return ExportOptions {
+            cell_width: 12,
+            cell_height: 24,
+            font: BitmapFont::builtin(),
+            foreground: (229, 229, 229),
+            background: (0, 0, 0),
+        };
+    }
+}
+
+impl ExportOptions {
+    // Cells of 12 by 24 pixels in the bundled font, light gray on black
+    pub fn new() -> ExportOptions {
+        return ExportOptions::default();
+    }
+
+    // Return a copy of the options with a different size of the cells in pixels
+    pub fn with_cell_size(mut self, width: i32, height: i32) -> ExportOptions {
+        self.cell_width = width;
+        self.cell_height = height;
+        return self;
+    }
+
+    // Return a copy of the options with a different font
+    pub fn with_font(mut self, font: BitmapFont) -> ExportOptions {
+        self.font = font;
+        return self;
+    }
+
+    // Return a copy of the options with different default colours
+    pub fn with_colors(mut self, foreground: (u8, u8, u8), background: (u8, u8, u8)) -> ExportOptions {
+        self.foreground = foreground;
+        self.background = background;
+        return self;
+    }
+}
+
+/* Render rows of cells into an image, `width` cells per row
+ * Fails with InvalidSize, for the cell size, if the image would have more pixels than an i32 can count
+*/
+pub(crate) fn render(cells: &[Cell], width: i32, options: &ExportOptions) -> Result<Image> {
+    let cell_width = options.cell_width.max(1);
+    let cell_height = options.cell_height.max(1);
+    let height = cells.len() as i32 / width.max(1);
+
+    let too_large = BsGraphError::InvalidSize { width: options.cell_width, height: options.cell_height };
+    let Some((image_width, image_height)) = width.max(0).checked_mul(cell_width).zip(height.checked_mul(cell_height)) else {
+        return Err(too_large);
+    };
+    let size = rect::area(image_width, image_height).map_err(|_| too_large)?;
+    let mut pixels: Vec<Rgba> = vec![(0, 0, 0, 255); size];
+
+    let colors: Vec<(Rgba, Rgba)> = cells
+        .iter()
+        .map(|cell| {
+            let fg = rgb(cell.fg, options.foreground);
+            let bg = rgb(cell.bg, options.background);
+            return if cell.attrs.reverse { (bg, fg) } else { (fg, bg) };
+        })
+        .collect();
+
+    // Backgrounds go first, as wide glyphs spill over the continuation cell to their right
+    for (i, (_, bg)) in colors.iter().enumerate() {
+        let (left, top) = (i as i32 % width * cell_width, i as i32 / width * cell_height);
+        for y in top..top + cell_height {
+            for x in left..left + cell_width {
+                pixels[(y * image_width + x) as usize] = *bg;
+            }
+        }
+    }
+
+    for (i, (cell, (fg, _))) in cells.iter().zip(&colors).enumerate() {
+        let (left, top) = (i as i32 % width * cell_width, i as i32 / width * cell_height);
+        let glyph_width = cell_width * cell.width().max(1) as i32;
+        let shape = glyph_shape(cell, glyph_width, cell_height, options);
+
+        for y in 0..cell_height {
+            for x in 0..glyph_width {
+                let set = shape(x, y) || (cell.attrs.underline && y == cell_height - 1 && x < cell_width);
+                if set && left + x < image_width {
+                    pixels[((top + y) * image_width + left + x) as usize] = *fg;
+                }
+            }
+        }
+    }
+
+    return Image::from_rgba(image_width, image_height, pixels);
+}
+
+// Colour a cell shows, with the default colour standing in for Color::Default
+fn rgb(color: Color, default: (u8, u8, u8)) -> Rgba {
+    let (r, g, b) = color.to_rgb().unwrap_or(default);
+    return (r, g, b, 255);
+}
+
+/* Pixels of the glyph of a cell that are set, in a box of the given size
+ * Block elements and braille are drawn as shapes, and anything else with the font
+*/
+fn glyph_shape<'a>(cell: &Cell, width: i32, height: i32, options: &'a ExportOptions) -> Box<dyn Fn(i32, i32) -> bool + 'a> {
+    let character = cell.glyph.chars().next().unwrap_or(' ');
+
+    if let Some((columns, rows, mask)) = block_pixels(character) {
+        return Box::new(move |x, y| mask & (1 << ((y * rows / height) * columns + x * columns / width)) != 0);
+    }
+
+    if let Some(dots) = (character as u32).checked_sub(BRAILLE_BLANK).and_then(|dots| u8::try_from(dots).ok()) {
+        return Box::new(move |x, y| {
+            // Every dot takes the middle of its square of a 2 by 4 grid
+            let (column, row) = (x * 2 / width, y * 4 / height);
+            let (inner_x, inner_y) = (x * 2 % width * 4 / width, y * 4 % height * 4 / height);
+            let bit = BRAILLE_DOTS[column as usize][row as usize];
+            return dots & bit != 0 && (1..3).contains(&inner_x) && (1..3).contains(&inner_y);
+        });
+    }
+
+    if character == ' ' || cell.is_continuation() {
+        return Box::new(|_, _| false);
+    }
+
+    let Some(glyph) = options.font.glyph(character) else {
+        return Box::new(|_, _| false);
+    };
+
+    // Scale the glyph up as much as fits, and center it
+    let font_height = options.font.height().max(1) as i32;
+    let scale = (width / glyph.width().max(1) as i32).min(height / font_height).max(1);
+    let offset_x = (width - glyph.width() as i32 * scale) / 2;
+    let offset_y = (height - font_height * scale) / 2;
+    let bold = cell.attrs.bold;
+
+    return Box::new(move |x, y| {
+        let (gx, gy) = (x - offset_x, y - offset_y);
+        if gx < 0 || gy < 0 {
+            return false;
+        }
+
+        // Bold glyphs are drawn twice, the second time one pixel to the right
+        let set = |gx: i32| gx >= 0 && glyph.pixel((gx / scale) as usize, (gy / scale) as usize);
+        return set(gx) || (bold && set(gx - 1));
+    });
+}
@@ -0,0 +1,375 @@
+/* FIGlet fonts for large banner text
+ *
+ * A FIGlet font (.flf) draws every character as a small block of text.
+ * When a line is rendered the characters are moved together as far as
+ * the layout of the font allows: full width keeps them apart, kerning
+ * makes them touch, and smushing overlaps them by one more column,
+ * merging the two characters that meet following the rules of the font.
+ *
+ * The format is described in http://www.jave.de/figlet/figfont.html
+*/
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{BsGraphError, Result};
+
+// Horizontal smushing rules of the font header
+const SMUSH_EQUAL: u32 = 1;
+const SMUSH_UNDERSCORE: u32 = 2;
+const SMUSH_HIERARCHY: u32 = 4;
+const SMUSH_PAIR: u32 = 8;
+const SMUSH_BIG_X: u32 = 16;
+const SMUSH_HARDBLANK: u32 = 32;
+
+// Horizontal layout bits of the full layout
+const LAYOUT_KERNING: i32 = 64;
+const LAYOUT_SMUSHING: i32 = 128;
+
+// Characters every font must define, after the printable ASCII characters
+const DEUTSCH: [char; 7] = ['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß'];
+
+// Bundled fonts
+const BLOCK: &str = include_str!("../fonts/block.flf");
+const SMALL: &str = include_str!("../fonts/small.flf");
+
+// How the characters of a line are put together
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layout {
+    FullWidth,
+    Kerning,
+    // Smushing with the given rules, no rules at all means universal smushing
+    Smushing(u32),
+}
+
+// A loaded FIGlet font
+#[derive(Clone, Debug)]
+pub struct FigFont {
+    height: usize,
+    hardblank: char,
+    layout: Layout,
+    glyphs: HashMap<char, Vec<Vec<char>>>,
+}
+
+impl FigFont {
+    /* Parse a font from the contents of a .flf file
+     * Fails with InvalidFont if the file isn't a valid FIGlet font
+    */
+    pub fn parse(source: &str) -> Result<FigFont> {
+        let mut lines = source.lines();
+
+        // flf2a$ height baseline max_length old_layout comment_lines [direction full_layout]
+        let header = lines.next().unwrap_or_default();
+        let signature = header.strip_prefix("flf2a").ok_or_else(|| invalid("missing the flf2a signature"))?;
+        let hardblank = signature.chars().next().ok_or_else(|| invalid("missing the hardblank"))?;
+
+        let fields: Vec<i32> = signature[hardblank.len_utf8()..]
+            .split_whitespace()
+            .map(|field| field.parse::<i32>())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| invalid("the header has a field that isn't a number"))?;
+        if fields.len() < 5 {
+            return Err(invalid("the header is too short"));
+        }
+
+        let height = usize::try_from(fields[0]).ok().filter(|height| *height > 0).ok_or_else(|| invalid("the height must be positive"))?;
+        let layout = match fields.get(6) {
+            Some(full) => full_layout(*full),
+            None => old_layout(fields[3]),
+        };
+
+        for _ in 0..fields[4].max(0) {
+            lines.next();
+        }
+
+        let mut font = FigFont {
+            height,
+            hardblank,
+            layout,
+            glyphs: HashMap::new(),
+        };
+
+        // The required characters come in order, without a code
+        for character in (' '..='~').chain(DEUTSCH) {
+            match read_glyph(&mut lines, height) {
+                Some(glyph) => font.glyphs.insert(character, glyph),
+                // Plenty of fonts stop before the Deutsch characters
+                None if character > '~' => return Ok(font),
+                None => return Err(invalid(&format!("the font ends before {:?}", character))),
+            };
+        }
+
+        // Then any other character, each one after a line with its code
+        while let Some(line) = lines.next() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let code = line.split_whitespace().next().and_then(parse_code);
+            let glyph = read_glyph(&mut lines, height).ok_or_else(|| invalid("the font ends in the middle of a character"))?;
+
+            // Negative codes are never shown, and broken ones are skipped like figlet does
+            if let Some(character) = code.and_then(|code| u32::try_from(code).ok()).and_then(char::from_u32) {
+                font.glyphs.insert(character, glyph);
+            }
+        }
+
+        return Ok(font);
+    }
+
+    /* Load a font from a .flf file
+     * Fails with Io if it can't be read, or InvalidFont if it isn't a valid font
+    */
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<FigFont> {
+        let source = std::fs::read_to_string(path).map_err(BsGraphError::Io)?;
+        return FigFont::parse(&source);
+    }
+
+    // Bundled font, 7 rows tall and drawn with full blocks
+    pub fn block() -> FigFont {
+        return FigFont::parse(BLOCK).expect("the bundled block font is valid");
+    }
+
+    // Bundled font, 3 rows tall and drawn with half blocks
+    pub fn small() -> FigFont {
+        return FigFont::parse(SMALL).expect("the bundled small font is valid");
+    }
+
+    // Number of rows of every line of text
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    // Check if the font can draw the character
+    pub fn has_glyph(&self, character: char) -> bool {
+        return self.glyphs.contains_key(&character);
+    }
+
+    /* Render text into the rows of a banner
+     *
+     * Every line of the text takes as many rows as the font is tall.
+     * Characters the font doesn't have are drawn as its character 0,
+     * or left out if it doesn't have one either.
+    */
+    pub fn render(&self, text: &str) -> Vec<String> {
+        let mut rows = Vec::new();
+
+        for line in text.lines() {
+            let mut output = vec![Vec::new(); self.height];
+            let mut previous_width = 0;
+
+            for character in line.chars() {
+                let Some(glyph) = self.glyphs.get(&character).or_else(|| self.glyphs.get(&'\0')) else {
+                    continue;
+                };
+
+                let width = glyph.first().map_or(0, |row| row.len());
+                let overlap = self.overlap(&output, glyph, previous_width, width);
+                self.append(&mut output, glyph, overlap, previous_width, width);
+                previous_width = width;
+            }
+
+            // Hardblanks only matter while the characters are put together
+            rows.extend(output.into_iter().map(|row| {
+                row.into_iter().map(|c| if c == self.hardblank { ' ' } else { c }).collect::<String>()
+            }));
+        }
+
+        return rows;
+    }
+}
+
+// Implement private methods for FigFont
+impl FigFont {
+    /* Merge two characters that end up in the same column
+     * Returns None if they can't be merged
+    */
+    fn smush(&self, left: char, right: char, left_width: usize, right_width: usize) -> Option<char> {
+        if left == ' ' {
+            return Some(right);
+        }
+        if right == ' ' {
+            return Some(left);
+        }
+
+        // Characters of a single column are never merged
+        if left_width < 2 || right_width < 2 {
+            return None;
+        }
+
+        let rules = match self.layout {
+            Layout::Smushing(rules) => rules,
+            _ => return None,
+        };
+
+        if rules == 0 {
+            // Universal smushing, the right character wins over anything but a hardblank
+            if left == self.hardblank {
+                return Some(right);
+            }
+            if right == self.hardblank {
+                return Some(left);
+            }
+            return Some(right);
+        }
+
+        if rules & SMUSH_HARDBLANK != 0 && left == self.hardblank && right == self.hardblank {
+            return Some(left);
+        }
+        if left == self.hardblank || right == self.hardblank {
+            return None;
+        }
+
+        if rules & SMUSH_EQUAL != 0 && left == right {
+            return Some(left);
+        }
+
+        if rules & SMUSH_UNDERSCORE != 0 {
+            const BORDERS: &str = "|/\\[]{}()<>";
+            if left == '_' && BORDERS.contains(right) {
+                return Some(right);
+            }
+            if right == '_' && BORDERS.contains(left) {
+                return Some(left);
+            }
+        }
+
+        if rules & SMUSH_HIERARCHY != 0 {
+            // Characters of a later class win over the earlier ones
+            const CLASSES: [&str; 6] = ["|", "/\\", "[]", "{}", "()", "<>"];
+            let class = |c: char| CLASSES.iter().position(|class| class.contains(c));
+
+            if let (Some(left_class), Some(right_class)) = (class(left), class(right)) {
+                if left_class < right_class {
+                    return Some(right);
+                }
+                if right_class < left_class {
+                    return Some(left);
+                }
+            }
+        }
+
+        if rules & SMUSH_PAIR != 0 && matches!((left, right), ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(')) {
+            return Some('|');
+        }
+
+        if rules & SMUSH_BIG_X != 0 {
+            match (left, right) {
+                ('/', '\\') => return Some('|'),
+                ('\\', '/') => return Some('Y'),
+                ('>', '<') => return Some('X'),
+                _ => {},
+            }
+        }
+
+        return None;
+    }
+
+    // Number of columns a glyph can be moved over the end of the output
+    fn overlap(&self, output: &[Vec<char>], glyph: &[Vec<char>], previous_width: usize, width: usize) -> usize {
+        if self.layout == Layout::FullWidth {
+            return 0;
+        }
+
+        let mut overlap = width;
+        for (row, glyph_row) in output.iter().zip(glyph) {
+            // Blanks at the end of the output and at the start of the glyph
+            let trailing = row.iter().rev().take_while(|c| **c == ' ').count();
+            let leading = glyph_row.iter().take_while(|c| **c == ' ').count();
+            let mut amount = trailing + leading;
+
+            // One column more if the characters that meet can be merged
+            if let (Some(left), Some(right)) = (row.iter().rev().find(|c| **c != ' '), glyph_row.get(leading)) {
+                if self.smush(*left, *right, previous_width, width).is_some() {
+                    amount += 1;
+                }
+            }
+
+            overlap = overlap.min(amount);
+        }
+
+        return overlap.min(width).min(output.iter().map(|row| row.len()).min().unwrap_or(0));
+    }
+
+    // Add a glyph to the end of the output, moved over it by `overlap` columns
+    fn append(&self, output: &mut [Vec<char>], glyph: &[Vec<char>], overlap: usize, previous_width: usize, width: usize) {
+        for (row, glyph_row) in output.iter_mut().zip(glyph) {
+            let start = row.len() - overlap;
+
+            for (i, right) in glyph_row.iter().enumerate() {
+                match row.get_mut(start + i) {
+                    Some(left) => *left = self.smush(*left, *right, previous_width, width).unwrap_or(*right),
+                    None => row.push(*right),
+                }
+            }
+        }
+    }
+}
+
+// Layout given by the old layout field of the header
+fn old_layout(old: i32) -> Layout {
+    return match old {
+        -1 => Layout::FullWidth,
+        0 => Layout::Kerning,
+        rules => Layout::Smushing((rules & 63) as u32),
+    };
+}
+
+// Layout given by the full layout field of the header, which overrides the old one
+fn full_layout(full: i32) -> Layout {
+    if full & LAYOUT_SMUSHING != 0 {
+        return Layout::Smushing((full & 63) as u32);
+    }
+    if full & LAYOUT_KERNING != 0 {
+        return Layout::Kerning;
+    }
+
+    return Layout::FullWidth;
+}
+
+/* Read the rows of a glyph
+ * Every row ends with an end mark, which is repeated at the end of the last one
+*/
+fn read_glyph<'a>(lines: &mut impl Iterator<Item = &'a str>, height: usize) -> Option<Vec<Vec<char>>> {
+    let mut glyph = Vec::with_capacity(height);
+
+    for _ in 0..height {
+        let line = lines.next()?.trim_end();
+        let row = match line.chars().last() {
+            Some(mark) => line.trim_end_matches(mark),
+            None => line,
+        };
+
+        glyph.push(row.chars().collect::<Vec<char>>());
+    }
+
+    // Rows are meant to be of the same width, pad the ones that aren't
+    let width = glyph.iter().map(|row| row.len()).max().unwrap_or(0);
+    for row in glyph.iter_mut() {
+        row.resize(width, ' ');
+    }
+
+    return Some(glyph);
+}
+
+// Parse the code of a code tagged character, in decimal, hexadecimal or octal
+fn parse_code(code: &str) -> Option<i64> {
+    let (negative, digits) = match code.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, code),
+    };
+
+    let value = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse::<i64>().ok()?
+    };
+
+    return Some(if negative { -value } else { value });
+}
+
+fn invalid(reason: &str) -> BsGraphError {
+    return BsGraphError::InvalidFont(reason.to_owned());
+}
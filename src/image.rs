@@ -0,0 +1,729 @@
+/* Raster images
+ *
+ * Images are loaded from PPM and PGM files (the binary and the plain text
+ * kinds), from uncompressed BMP files and, with the png feature, from PNG
+ * files, and saved as PPM or PNG. They are drawn on the display scaled to
+ * any number of cells, as characters of a brightness ramp, as coloured half
+ * blocks or as braille dots, optionally dithered to hide the few levels
+ * each cell can show.
+*/
+
+use std::path::Path;
+
+use crate::braille::{BRAILLE_BLANK, BRAILLE_DOTS};
+use crate::{BsGraphError, Cell, Color, ColorDepth, Result, Sprite};
+
+// Brightness ramp used by default, from dark to bright
+const RAMP: &str = " .:-=+*#%@";
+
+// Thresholds of ordered dithering, in sixteenths
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+// Magic number at the start of PNG files
+const PNG_MAGIC: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// A pixel of an image, as red, green, blue and alpha
+pub type Rgba = (u8, u8, u8, u8);
+
+// An image made of pixels, each one with its own colour and opacity
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: i32,
+    height: i32,
+    pixels: Vec<Rgba>,      // Row-major, width * height pixels
+}
+
+// How the pixels of an image are turned into cells
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageMode {
+    // Every pixel takes a cell, drawn with the character of the ramp as bright as it is
+    #[default]
+    Ascii,
+    // Every cell holds two pixels, one above the other, drawn with half blocks in their colours
+    HalfBlock,
+    // Every cell holds 2 by 4 pixels, drawn as the dots of a braille character that are lit
+    Braille,
+}
+
+// How the colours or brightness of the pixels are reduced to the levels a cell can show
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    // Every pixel gets the closest level
+    #[default]
+    None,
+    // The error of every pixel is spread over the pixels next to it
+    FloydSteinberg,
+    // Pixels are moved up or down a level following a fixed 4 by 4 pattern
+    Ordered,
+}
+
+/* How an image is drawn
+ *
+ * The ramp goes from dark to bright, and it's used backwards if `invert`
+ * is set, e.g. for dark text on a light terminal. Ascii and braille cells
+ * are only coloured if `color` is set, while half blocks always are.
+ * Colours are reduced to `depth`, which is where dithering helps half blocks.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageOptions {
+    pub mode: ImageMode,
+    pub dither: Dither,
+    pub ramp: String,
+    pub invert: bool,
+    pub color: bool,
+    pub depth: ColorDepth,
+}
+
+impl Default for ImageOptions {
+    fn default() -> ImageOptions {
+        return ImageOptions {
+            mode: ImageMode::Ascii,
+            dither: Dither::None,
+            ramp: RAMP.to_owned(),
+            invert: false,
+            color: false,
+            depth: ColorDepth::TrueColor,
+        };
+    }
+}
+
+impl ImageOptions {
+    // Characters of the default ramp, without dithering or colours
+    pub fn new() -> ImageOptions {
+        return ImageOptions::default();
+    }
+
+    // Return a copy of the options with a different mode
+    pub fn with_mode(mut self, mode: ImageMode) -> ImageOptions {
+        self.mode = mode;
+        return self;
+    }
+
+    // Return a copy of the options with a different dithering
+    pub fn with_dither(mut self, dither: Dither) -> ImageOptions {
+        self.dither = dither;
+        return self;
+    }
+
+    // Return a copy of the options with a different ramp, from dark to bright
+    pub fn with_ramp(mut self, ramp: &str) -> ImageOptions {
+        self.ramp = ramp.to_owned();
+        return self;
+    }
+
+    // Return a copy of the options with the brightness inverted or not
+    pub fn with_invert(mut self, invert: bool) -> ImageOptions {
+        self.invert = invert;
+        return self;
+    }
+
+    // Return a copy of the options with ascii and braille cells coloured or not
+    pub fn with_color(mut self, color: bool) -> ImageOptions {
+        self.color = color;
+        return self;
+    }
+
+    // Return a copy of the options with colours reduced to a different depth
+    pub fn with_depth(mut self, depth: ColorDepth) -> ImageOptions {
+        self.depth = depth;
+        return self;
+    }
+}
+
+impl Image {
+    /* Create an image from its pixels in row-major order
+     * Fails with SizeMismatch if there aren't width * height pixels
+    */
+    pub fn from_rgba(width: i32, height: i32, pixels: Vec<Rgba>) -> Result<Image> {
+        let Some(size) = (width.max(0) as usize).checked_mul(height.max(0) as usize) else {
+            return Err(BsGraphError::SizeMismatch { expected: usize::MAX, actual: pixels.len() });
+        };
+        if pixels.len() != size {
+            return Err(BsGraphError::SizeMismatch { expected: size, actual: pixels.len() });
+        }
+
+        return Ok(Image {
+            width: width.max(0),
+            height: height.max(0),
+            pixels,
+        });
+    }
+
+    /* Create an opaque image from its pixels in row-major order
+     * Fails with SizeMismatch if there aren't width * height pixels
+    */
+    pub fn from_rgb(width: i32, height: i32, pixels: Vec<(u8, u8, u8)>) -> Result<Image> {
+        return Image::from_rgba(width, height, pixels.into_iter().map(|(r, g, b)| (r, g, b, 255)).collect());
+    }
+
+    /* Parse an image from the contents of a PPM or PGM file, binary or plain
+     * Fails with InvalidImage if the file isn't a valid image
+    */
+    pub fn parse_pnm(bytes: &[u8]) -> Result<Image> {
+        let mut position = 0;
+        let magic = pnm_token(bytes, &mut position).ok_or_else(|| invalid("missing the PNM magic number"))?;
+        let (channels, binary) = match magic {
+            b"P2" => (1, false),
+            b"P3" => (3, false),
+            b"P5" => (1, true),
+            b"P6" => (3, true),
+            _ => return Err(invalid("only PPM and PGM images are supported")),
+        };
+
+        let number = |position: &mut usize| -> Result<u32> {
+            let token = pnm_token(bytes, position).ok_or_else(|| invalid("the image ends too early"))?;
+            return std::str::from_utf8(token).ok().and_then(|token| token.parse().ok()).ok_or_else(|| invalid("expected a number"));
+        };
+
+        let width = number(&mut position)? as i32;
+        let height = number(&mut position)? as i32;
+        let max = number(&mut position)?;
+        if width < 0 || height < 0 || max == 0 || max > 65535 {
+            return Err(invalid("the size or maximum value is out of range"));
+        }
+
+        let out_of_range = || invalid("the size or maximum value is out of range");
+        let count = (width as usize).checked_mul(height as usize).and_then(|pixels| pixels.checked_mul(channels)).ok_or_else(out_of_range)?;
+        let samples: Vec<u32> = if binary {
+            // A single whitespace character comes between the header and the samples
+            let data = bytes.get(position + 1..).unwrap_or_default();
+            let size = if max < 256 { 1 } else { 2 };
+            if data.len() < count.checked_mul(size).ok_or_else(out_of_range)? {
+                return Err(invalid("the image ends too early"));
+            }
+
+            data.chunks(size).take(count).map(|sample| sample.iter().fold(0, |value, byte| value << 8 | *byte as u32)).collect()
+        } else {
+            (0..count).map(|_| number(&mut position)).collect::<Result<_>>()?
+        };
+
+        let scale = |sample: u32| (sample.min(max) * 255 / max) as u8;
+        let pixels = samples
+            .chunks(channels)
+            .map(|pixel| match pixel {
+                [gray] => (scale(*gray), scale(*gray), scale(*gray), 255),
+                _ => (scale(pixel[0]), scale(pixel[1]), scale(pixel[2]), 255),
+            })
+            .collect();
+
+        return Image::from_rgba(width, height, pixels);
+    }
+
+    /* Parse an image from the contents of an uncompressed BMP file
+     *
+     * Palette images of 1, 4 and 8 bits per pixel are supported, and so
+     * are 16, 24 and 32 bits per pixel, with or without bit fields.
+     * Fails with InvalidImage if the file isn't a valid image, or is compressed
+    */
+    pub fn parse_bmp(bytes: &[u8]) -> Result<Image> {
+        let u16_at = |offset: usize| -> Result<u32> {
+            let field = bytes.get(offset..offset + 2).ok_or_else(|| invalid("the header is too short"))?;
+            return Ok(u16::from_le_bytes(field.try_into().unwrap()) as u32);
+        };
+        let u32_at = |offset: usize| -> Result<u32> {
+            let field = bytes.get(offset..offset + 4).ok_or_else(|| invalid("the header is too short"))?;
+            return Ok(u32::from_le_bytes(field.try_into().unwrap()));
+        };
+
+        if !bytes.starts_with(b"BM") {
+            return Err(invalid("missing the BMP magic number"));
+        }
+
+        let data_offset = u32_at(10)? as usize;
+        let header_size = u32_at(14)? as usize;
+
+        // The old OS/2 header has 16 bit sizes and 3 byte palette entries
+        let (width, height, bits, compression, palette_size, entry_size) = if header_size == 12 {
+            (u16_at(18)? as i32, u16_at(20)? as i32, u16_at(24)?, 0, 0, 3)
+        } else if header_size >= 40 {
+            (u32_at(18)? as i32, u32_at(22)? as i32, u16_at(28)?, u32_at(30)?, u32_at(46)? as usize, 4)
+        } else {
+            return Err(invalid("unknown BMP header"));
+        };
+
+        // Rows go from the bottom up, unless the height is negative
+        let top_down = height < 0;
+        let height = height.checked_abs().ok_or_else(|| invalid("the size is out of range"))?;
+        if width < 0 {
+            return Err(invalid("the size is out of range"));
+        }
+
+        // Bit fields come in the header from version 3 on, or right after a version 1 header
+        const BITFIELDS: u32 = 3;
+        let (masks, alpha) = match (compression, bits) {
+            (0, 16) => ([0x7c00, 0x03e0, 0x001f], 0),
+            (0, 32) => ([0xff0000, 0x00ff00, 0x0000ff], 0),
+            (BITFIELDS, 16 | 32) => {
+                let masks = [u32_at(14 + 40)?, u32_at(14 + 44)?, u32_at(14 + 48)?];
+                let alpha = if header_size >= 56 { u32_at(14 + 52)? } else { 0 };
+                (masks, alpha)
+            },
+            (0, 1 | 4 | 8 | 24) => ([0; 3], 0),
+            (0 | BITFIELDS, _) => return Err(invalid("unsupported number of bits per pixel")),
+            _ => return Err(invalid("compressed bitmaps aren't supported")),
+        };
+
+        let palette = if bits <= 8 {
+            let count = if palette_size == 0 { 1 << bits } else { palette_size.min(1 << bits) };
+            let start = 14 + header_size;
+
+            (0..count)
+                .map(|i| bytes.get(start + i * entry_size..start + i * entry_size + 3).map(|bgr| (bgr[2], bgr[1], bgr[0], 255)))
+                .collect::<Option<Vec<Rgba>>>()
+                .ok_or_else(|| invalid("the palette is cut short"))?
+        } else {
+            Vec::new()
+        };
+
+        // Check that all the pixels are there before making room for them
+        let cut_short = || invalid("the pixels are cut short");
+        let row_size = (width as usize).checked_mul(bits as usize).ok_or_else(cut_short)?.div_ceil(32) * 4;
+        let end = row_size.checked_mul(height as usize).and_then(|size| size.checked_add(data_offset)).ok_or_else(cut_short)?;
+        let count = (width as usize).checked_mul(height as usize).ok_or_else(cut_short)?;
+        if end > bytes.len() {
+            return Err(cut_short());
+        }
+
+        let mut pixels = Vec::with_capacity(count);
+
+        for y in 0..height as usize {
+            let row = if top_down { y } else { height as usize - 1 - y };
+            let start = data_offset + row * row_size;
+            let data = bytes.get(start..start + row_size).ok_or_else(|| invalid("the pixels are cut short"))?;
+
+            for x in 0..width as usize {
+                let pixel = match bits {
+                    1 | 4 | 8 => {
+                        let bit = x * bits as usize;
+                        let index = (data[bit / 8] >> (8 - bits as usize - bit % 8)) & ((1 << bits) - 1);
+                        *palette.get(index as usize).ok_or_else(|| invalid("a pixel is outside of the palette"))?
+                    },
+                    24 => (data[x * 3 + 2], data[x * 3 + 1], data[x * 3], 255),
+                    _ => {
+                        let size = bits as usize / 8;
+                        let value = data[x * size..x * size + size].iter().rev().fold(0, |value, byte| value << 8 | *byte as u32);
+                        let opacity = if alpha == 0 { 255 } else { field(value, alpha) };
+                        (field(value, masks[0]), field(value, masks[1]), field(value, masks[2]), opacity)
+                    },
+                };
+                pixels.push(pixel);
+            }
+        }
+
+        return Image::from_rgba(width, height, pixels);
+    }
+
+    /* Parse an image from the contents of a PNG file
+     * Fails with InvalidImage if the file isn't a valid image
+    */
+    #[cfg(feature = "png")]
+    pub fn parse_png(bytes: &[u8]) -> Result<Image> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+        let mut reader = decoder.read_info().map_err(|error| invalid(&error.to_string()))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|error| invalid(&error.to_string()))?;
+
+        let pixels = buffer[..info.buffer_size()]
+            .chunks(info.color_type.samples())
+            .map(|pixel| match *pixel {
+                [gray] => (gray, gray, gray, 255),
+                [gray, alpha] => (gray, gray, gray, alpha),
+                [r, g, b] => (r, g, b, 255),
+                [r, g, b, alpha, ..] => (r, g, b, alpha),
+                [] => (0, 0, 0, 0),
+            })
+            .collect();
+
+        return Image::from_rgba(info.width as i32, info.height as i32, pixels);
+    }
+
+    /* Parse an image in any of the supported formats, told apart by its contents
+     * Fails with InvalidImage if it isn't a valid image in a supported format
+    */
+    pub fn decode(bytes: &[u8]) -> Result<Image> {
+        if bytes.starts_with(b"BM") {
+            return Image::parse_bmp(bytes);
+        }
+
+        if bytes.starts_with(&PNG_MAGIC) {
+            #[cfg(feature = "png")]
+            return Image::parse_png(bytes);
+
+            #[cfg(not(feature = "png"))]
+            return Err(invalid("PNG images need the png feature"));
+        }
+
+        return Image::parse_pnm(bytes);
+    }
+
+    /* Load an image from a file in any of the supported formats
+     * Fails with Io if it can't be read, or InvalidImage if it isn't a valid image
+    */
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Image> {
+        let bytes = std::fs::read(path).map_err(BsGraphError::Io)?;
+        return Image::decode(&bytes);
+    }
+
+    /* Encode the image as a binary PPM file
+     * PPM has no transparency, so the alpha of the pixels is left out
+    */
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for (r, g, b, _) in &self.pixels {
+            bytes.extend([*r, *g, *b]);
+        }
+
+        return bytes;
+    }
+
+    /* Encode the image as a PNG file
+     * Fails with InvalidImage if the image can't be encoded
+    */
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data: Vec<u8> = self.pixels.iter().flat_map(|(r, g, b, a)| [*r, *g, *b, *a]).collect();
+        let mut writer = encoder.write_header().map_err(|error| invalid(&error.to_string()))?;
+        writer.write_image_data(&data).map_err(|error| invalid(&error.to_string()))?;
+        writer.finish().map_err(|error| invalid(&error.to_string()))?;
+
+        return Ok(bytes);
+    }
+
+    /* Save the image to a file, as PNG if its extension is .png and as PPM otherwise
+     * Fails with Io if it can't be written, or InvalidImage if PNG is asked for without the png feature
+    */
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let png = path.as_ref().extension().is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+
+        if !png {
+            return std::fs::write(path, self.to_ppm()).map_err(BsGraphError::Io);
+        }
+
+        #[cfg(feature = "png")]
+        return std::fs::write(path, self.to_png()?).map_err(BsGraphError::Io);
+
+        #[cfg(not(feature = "png"))]
+        return Err(invalid("PNG images need the png feature"));
+    }
+
+    // Width of the image in pixels
+    pub fn width(&self) -> i32 {
+        return self.width;
+    }
+
+    // Height of the image in pixels
+    pub fn height(&self) -> i32 {
+        return self.height;
+    }
+
+    // Get the pixel at (x, y), None if it's outside of the image
+    pub fn get(&self, x: i32, y: i32) -> Option<Rgba> {
+        if x < 0 || x >= self.width || y < 0 || y >= self.height {
+            return None;
+        }
+
+        return Some(self.pixels[(y * self.width + x) as usize]);
+    }
+
+    /* Scale the image to a new size
+     * Every new pixel is the average of the pixels it covers, or the closest one when enlarging
+    */
+    pub fn resize(&self, width: i32, height: i32) -> Image {
+        let pixels = self
+            .sample(width.max(0), height.max(0))
+            .into_iter()
+            .map(|[r, g, b, a]| (r.round() as u8, g.round() as u8, b.round() as u8, a.round() as u8))
+            .collect();
+        return Image::from_rgba(width, height, pixels).unwrap();
+    }
+
+    /* Render the image into a sprite of the given size in cells
+     *
+     * The image is stretched over the whole sprite, so pick a size that
+     * keeps its aspect, remembering cells are about twice as tall as wide.
+     * Cells where the image is mostly transparent are transparent too.
+    */
+    pub fn render(&self, columns: i32, rows: i32, options: &ImageOptions) -> Sprite {
+        let columns = columns.max(0);
+        let rows = rows.max(0);
+
+        return match options.mode {
+            ImageMode::Ascii => self.render_ascii(columns, rows, options),
+            ImageMode::HalfBlock => self.render_half_blocks(columns, rows, options),
+            ImageMode::Braille => self.render_braille(columns, rows, options),
+        };
+    }
+}
+
+// Implement private methods for Image
+impl Image {
+    // Scale the image to a new size, as red, green, blue and alpha from 0 to 255
+    fn sample(&self, width: i32, height: i32) -> Vec<[f32; 4]> {
+        let mut samples = Vec::with_capacity(width.max(0) as usize * height.max(0) as usize);
+
+        // Range of pixels of the image covered by a pixel of the new size
+        let span = |i: i32, new: i32, old: i32| -> (i32, i32) {
+            let (i, new, old) = (i as u64, new as u64, old as u64);
+            let start = (i * old / new) as i32;
+            let end = ((i + 1) * old).div_ceil(new) as i32;
+            return (start, end.max(start + 1).min(old as i32));
+        };
+
+        for y in 0..height {
+            let (top, bottom) = span(y, height, self.height);
+
+            for x in 0..width {
+                let (left, right) = span(x, width, self.width);
+
+                // Colours are weighted by their alpha, so transparent pixels don't darken the rest
+                let mut sum = [0.0; 4];
+                for (r, g, b, a) in (top..bottom).flat_map(|py| (left..right).filter_map(move |px| self.get(px, py))) {
+                    let weight = a as f32 / 255.0;
+                    sum[0] += r as f32 * weight;
+                    sum[1] += g as f32 * weight;
+                    sum[2] += b as f32 * weight;
+                    sum[3] += a as f32;
+                }
+
+                let count = ((bottom - top) * (right - left)).max(1) as f32;
+                let weight = sum[3] / 255.0;
+                samples.push(match weight > 0.0 {
+                    true => [sum[0] / weight, sum[1] / weight, sum[2] / weight, sum[3] / count],
+                    false => [0.0; 4],
+                });
+            }
+        }
+
+        return samples;
+    }
+
+    // Brightness levels of the samples, from 0 to `levels` - 1
+    fn levels(samples: &[[f32; 4]], width: i32, levels: usize, options: &ImageOptions) -> Vec<usize> {
+        let top = levels.saturating_sub(1) as f32;
+        let values = samples
+            .iter()
+            .map(|sample| {
+                let brightness = luminance(sample) / 255.0;
+                [if options.invert { 1.0 - brightness } else { brightness } * top]
+            })
+            .collect();
+
+        return dither(values, width, options.dither, 1.0, |[value]| {
+            let level = value.round().clamp(0.0, top);
+            return (level as usize, [level]);
+        });
+    }
+
+    // Colours of the samples, reduced to a depth
+    fn colors(samples: &[[f32; 4]], width: i32, dither_mode: Dither, depth: ColorDepth) -> Vec<Color> {
+        // Roughly the distance between the colours of the palette
+        let spread = match depth {
+            ColorDepth::Ansi16 => 128.0,
+            ColorDepth::Ansi256 => 40.0,
+            _ => 0.0,
+        };
+        let dither_mode = if spread == 0.0 { Dither::None } else { dither_mode };
+
+        let values = samples.iter().map(|sample| [sample[0], sample[1], sample[2]]).collect();
+        return dither(values, width, dither_mode, spread, |[r, g, b]| {
+            let [r, g, b] = [r, g, b].map(|value| value.round().clamp(0.0, 255.0) as u8);
+            let color = Color::Rgb(r, g, b).downgrade(depth);
+            let (r, g, b) = color.to_rgb().unwrap_or((r, g, b));
+            return (color, [r as f32, g as f32, b as f32]);
+        });
+    }
+
+    fn render_ascii(&self, columns: i32, rows: i32, options: &ImageOptions) -> Sprite {
+        let ramp: Vec<char> = options.ramp.chars().collect();
+        let samples = self.sample(columns, rows);
+        let levels = Image::levels(&samples, columns, ramp.len(), options);
+        let colors = Image::colors(&samples, columns, Dither::None, options.depth);
+
+        let mut sprite = Sprite::new(columns, rows);
+        for (i, sample) in samples.iter().enumerate() {
+            let Some(glyph) = ramp.get(levels[i]) else {
+                continue;
+            };
+
+            if opaque(sample) {
+                let cell = Cell::new(*glyph);
+                let cell = if options.color { cell.with_fg(colors[i]) } else { cell };
+                sprite.set(i as i32 % columns, i as i32 / columns, cell);
+            }
+        }
+
+        return sprite;
+    }
+
+    fn render_half_blocks(&self, columns: i32, rows: i32, options: &ImageOptions) -> Sprite {
+        let samples = self.sample(columns, rows * 2);
+        let colors = Image::colors(&samples, columns, options.dither, options.depth);
+
+        let mut sprite = Sprite::new(columns, rows);
+        for y in 0..rows {
+            for x in 0..columns {
+                let top = (y * 2 * columns + x) as usize;
+                let bottom = top + columns as usize;
+
+                let cell = match (opaque(&samples[top]), opaque(&samples[bottom])) {
+                    (true, true) => Cell::new('▀').with_fg(colors[top]).with_bg(colors[bottom]),
+                    (true, false) => Cell::new('▀').with_fg(colors[top]),
+                    (false, true) => Cell::new('▄').with_fg(colors[bottom]),
+                    (false, false) => continue,
+                };
+                sprite.set(x, y, cell);
+            }
+        }
+
+        return sprite;
+    }
+
+    fn render_braille(&self, columns: i32, rows: i32, options: &ImageOptions) -> Sprite {
+        let width = columns * 2;
+        let samples = self.sample(width, rows * 4);
+        let lit = Image::levels(&samples, width, 2, options);
+
+        let mut sprite = Sprite::new(columns, rows);
+        for y in 0..rows {
+            for x in 0..columns {
+                let mut dots = 0u8;
+                let mut any_opaque = false;
+                let mut sum = [0.0; 4];
+
+                for (column, bits) in BRAILLE_DOTS.iter().enumerate() {
+                    for (row, bit) in bits.iter().enumerate() {
+                        let i = ((y * 4 + row as i32) * width + x * 2 + column as i32) as usize;
+                        if !opaque(&samples[i]) {
+                            continue;
+                        }
+
+                        any_opaque = true;
+                        if lit[i] == 1 {
+                            dots |= bit;
+                            for (total, value) in sum.iter_mut().zip(samples[i]) {
+                                *total += value;
+                            }
+                        }
+                    }
+                }
+
+                if !any_opaque {
+                    continue;
+                }
+
+                let cell = Cell::new(char::from_u32(BRAILLE_BLANK + dots as u32).unwrap());
+                let cell = if options.color && dots != 0 {
+                    let count = dots.count_ones() as f32;
+                    let average = [sum[0] / count, sum[1] / count, sum[2] / count, 255.0];
+                    cell.with_fg(Image::colors(&[average], 1, Dither::None, options.depth)[0])
+                } else {
+                    cell
+                };
+                sprite.set(x, y, cell);
+            }
+        }
+
+        return sprite;
+    }
+}
+
+// Check if a sample is opaque enough to be drawn
+fn opaque(sample: &[f32; 4]) -> bool {
+    return sample[3] >= 128.0;
+}
+
+// Brightness of a sample from 0 to 255, as seen by the eye
+fn luminance(sample: &[f32; 4]) -> f32 {
+    return 0.2126 * sample[0] + 0.7152 * sample[1] + 0.0722 * sample[2];
+}
+
+/* Reduce values to the few that can be shown
+ *
+ * `pick` returns what is shown for a value and the value it stands for.
+ * Ordered dithering moves values by up to half of `spread` either way
+ * before picking, and Floyd-Steinberg passes the difference between a
+ * value and what was picked for it to the values right and below it.
+*/
+fn dither<T, const N: usize, F: Fn([f32; N]) -> (T, [f32; N])>(mut values: Vec<[f32; N]>, width: i32, mode: Dither, spread: f32, pick: F) -> Vec<T> {
+    let width = width.max(1) as usize;
+    let mut picked = Vec::with_capacity(values.len());
+
+    for i in 0..values.len() {
+        let (x, y) = (i % width, i / width);
+        let value = match mode {
+            Dither::Ordered => {
+                let threshold = (BAYER[y % 4][x % 4] as f32 + 0.5) / 16.0;
+                values[i].map(|channel| channel + (0.5 - threshold) * spread)
+            },
+            _ => values[i],
+        };
+
+        let (shown, chosen) = pick(value);
+        picked.push(shown);
+
+        if mode == Dither::FloydSteinberg {
+            let mut spread_error = |dx: isize, dy: usize, weight: f32| {
+                let Some(target_x) = x.checked_add_signed(dx).filter(|target_x| *target_x < width) else {
+                    return;
+                };
+
+                if let Some(target) = values.get_mut((y + dy) * width + target_x) {
+                    for channel in 0..N {
+                        target[channel] += (value[channel] - chosen[channel]) * weight;
+                    }
+                }
+            };
+
+            spread_error(1, 0, 7.0 / 16.0);
+            spread_error(-1, 1, 3.0 / 16.0);
+            spread_error(0, 1, 5.0 / 16.0);
+            spread_error(1, 1, 1.0 / 16.0);
+        }
+    }
+
+    return picked;
+}
+
+// Next token of the header of a PNM file, skipping whitespace and comments
+fn pnm_token<'a>(bytes: &'a [u8], position: &mut usize) -> Option<&'a [u8]> {
+    loop {
+        match bytes.get(*position)? {
+            byte if byte.is_ascii_whitespace() => *position += 1,
+            b'#' => {
+                while bytes.get(*position).is_some_and(|byte| *byte != b'\n') {
+                    *position += 1;
+                }
+            },
+            _ => break,
+        }
+    }
+
+    let start = *position;
+    while bytes.get(*position).is_some_and(|byte| !byte.is_ascii_whitespace() && *byte != b'#') {
+        *position += 1;
+    }
+
+    return Some(&bytes[start..*position]);
+}
+
+// Value of a bit field of a pixel, scaled to 0..255
+fn field(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+
+    let bits = (value & mask) >> mask.trailing_zeros();
+    let max = mask >> mask.trailing_zeros();
+    return (bits as u64 * 255 / max as u64) as u8;
+}
+
+fn invalid(reason: &str) -> BsGraphError {
+    return BsGraphError::InvalidImage(reason.to_owned());
+}
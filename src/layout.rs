@@ -0,0 +1,373 @@
+/* Layout of text blocks
+ *
+ * Text is handled as a list of grapheme clusters, each one with its own
+ * style, so plain and styled text go through the same path. The text is
+ * split in paragraphs at the newlines, every paragraph is wrapped into
+ * lines that fit the width of the block, and the lines are then aligned
+ * inside of the block.
+*/
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{Rect, Style};
+
+// Horizontal alignment of the lines of a text block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+    // Stretch the spaces between words so every line fills the whole width,
+    // except for the last line of each paragraph, which is left aligned
+    Justify,
+}
+
+// Vertical alignment of the lines of a text block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+/* How the text of a text block is laid out
+ *
+ * Long lines are wrapped between words by default, words that don't fit
+ * in a line on their own are broken wherever they reach the edge.
+ * Text that doesn't fit in the block is cut, and ends with the ellipsis
+ * when there is one.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextOptions {
+    pub align: Align,
+    pub valign: VAlign,
+    pub wrap: bool,
+    pub ellipsis: Option<String>,
+}
+
+impl Default for TextOptions {
+    fn default() -> TextOptions {
+        return TextOptions {
+            align: Align::Left,
+            valign: VAlign::Top,
+            wrap: true,
+            ellipsis: None,
+        };
+    }
+}
+
+impl TextOptions {
+    // Left and top aligned text that wraps, without an ellipsis
+    pub fn new() -> TextOptions {
+        return TextOptions::default();
+    }
+
+    // Return a copy of the options with a different horizontal alignment
+    pub fn with_align(mut self, align: Align) -> TextOptions {
+        self.align = align;
+        return self;
+    }
+
+    // Return a copy of the options with a different vertical alignment
+    pub fn with_valign(mut self, valign: VAlign) -> TextOptions {
+        self.valign = valign;
+        return self;
+    }
+
+    // Return a copy of the options that wraps long lines or cuts them
+    pub fn with_wrap(mut self, wrap: bool) -> TextOptions {
+        self.wrap = wrap;
+        return self;
+    }
+
+    // Return a copy of the options that ends cut text with the given ellipsis
+    pub fn with_ellipsis(mut self, ellipsis: &str) -> TextOptions {
+        self.ellipsis = Some(ellipsis.to_owned());
+        return self;
+    }
+}
+
+// A grapheme cluster placed by the layout, in display coordinates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Placed<'a> {
+    pub x: i32,
+    pub y: i32,
+    pub text: &'a str,
+    pub style: Style,
+}
+
+// A grapheme cluster of a line
+#[derive(Clone, Copy, Debug)]
+struct Glyph<'a> {
+    text: &'a str,
+    style: Style,
+    width: i32,
+    space: bool,
+}
+
+// A line of a text block, before it's aligned
+#[derive(Debug, Default)]
+struct Line<'a> {
+    glyphs: Vec<Glyph<'a>>,
+    width: i32,
+    last: bool,     // Whether it ends a paragraph, or was cut
+}
+
+// Implement private methods for Line
+impl<'a> Line<'a> {
+    fn push(&mut self, glyph: Glyph<'a>) {
+        self.width += glyph.width;
+        self.glyphs.push(glyph);
+    }
+
+    // Check if there is anything but whitespace in the line
+    fn has_words(&self) -> bool {
+        return self.glyphs.iter().any(|glyph| !glyph.space);
+    }
+
+    // Drop the whitespace at the end of the line
+    fn trim_end(&mut self) {
+        while self.glyphs.last().is_some_and(|glyph| glyph.space) {
+            let glyph = self.glyphs.pop().unwrap();
+            self.width -= glyph.width;
+        }
+    }
+
+    // Number of runs of whitespace between two words
+    fn gaps(&self) -> i32 {
+        let mut gaps = 0;
+        for pair in self.glyphs.windows(2) {
+            if !pair[0].space && pair[1].space {
+                gaps += 1;
+            }
+        }
+
+        return gaps;
+    }
+
+    /* Cut the line to fit in the given width
+     * The ellipsis takes the style of the last glyph that is kept
+    */
+    fn cut(&mut self, width: i32, ellipsis: &[&'a str]) {
+        let ellipsis_width: i32 = ellipsis.iter().map(|text| glyph_width(text)).sum();
+        let room = if ellipsis_width <= width { width - ellipsis_width } else { width };
+
+        while self.width > room {
+            let glyph = self.glyphs.pop().unwrap();
+            self.width -= glyph.width;
+        }
+
+        if ellipsis_width <= width && !ellipsis.is_empty() {
+            self.trim_end();
+
+            let style = self.glyphs.last().map(|glyph| glyph.style).unwrap_or_default();
+            for text in ellipsis {
+                self.push(Glyph { text, style, width: glyph_width(text), space: false });
+            }
+        }
+
+        self.last = true;
+    }
+}
+
+/* Columns taken by a grapheme cluster on the screen
+ * Control characters don't take any space
+*/
+pub(crate) fn glyph_width(grapheme: &str) -> i32 {
+    if grapheme.chars().next().is_none_or(char::is_control) {
+        return 0;
+    }
+
+    return grapheme.width() as i32;
+}
+
+// Split text in grapheme clusters that all have the same style
+pub(crate) fn graphemes(text: &str, style: Style) -> Vec<(&str, Style)> {
+    return text.graphemes(true).map(|grapheme| (grapheme, style)).collect();
+}
+
+/* Lay out styled text inside of a rectangle
+ *
+ * Returns where every grapheme cluster goes, and the area covered by the
+ * lines of text. Nothing is placed outside of the rectangle.
+*/
+pub(crate) fn layout<'a>(text: &[(&'a str, Style)], rect: Rect, options: &'a TextOptions) -> (Vec<Placed<'a>>, Rect) {
+    let mut lines = Vec::new();
+
+    let mut paragraphs: Vec<&[(&str, Style)]> = text.split(|(grapheme, _)| is_newline(grapheme)).collect();
+    // A newline at the end doesn't start another paragraph
+    if paragraphs.len() > 1 && paragraphs.last().is_some_and(|paragraph| paragraph.is_empty()) {
+        paragraphs.pop();
+    }
+    if text.is_empty() {
+        paragraphs.clear();
+    }
+
+    for paragraph in paragraphs {
+        let glyphs = paragraph.iter().filter_map(|(text, style)| glyph(text, *style));
+
+        if options.wrap {
+            wrap(glyphs, rect.width, &mut lines);
+        } else {
+            let mut line = Line::default();
+            glyphs.for_each(|glyph| line.push(glyph));
+            line.trim_end();
+            line.last = true;
+            lines.push(line);
+        }
+    }
+
+    // Cut whatever doesn't fit in the rectangle
+    let ellipsis: Vec<&str> = match &options.ellipsis {
+        Some(ellipsis) => ellipsis.graphemes(true).collect(),
+        None => Vec::new(),
+    };
+    let overflow = lines.len() > rect.height.max(0) as usize;
+    lines.truncate(rect.height.max(0) as usize);
+
+    let count = lines.len();
+    for (i, line) in lines.iter_mut().enumerate() {
+        if line.width > rect.width || (overflow && i + 1 == count) {
+            line.cut(rect.width.max(0), &ellipsis);
+        }
+    }
+
+    let top = match options.valign {
+        VAlign::Top => 0,
+        VAlign::Middle => (rect.height - count as i32) / 2,
+        VAlign::Bottom => rect.height - count as i32,
+    };
+
+    let mut placed = Vec::new();
+    let mut left = i32::MAX;
+    let mut right = i32::MIN;
+
+    for (row, line) in lines.iter().enumerate() {
+        let y = rect.y + top + row as i32;
+        let mut x = rect.x + match options.align {
+            Align::Left | Align::Justify => 0,
+            Align::Center => (rect.width - line.width) / 2,
+            Align::Right => rect.width - line.width,
+        };
+
+        // Spread the free columns over the gaps between words
+        let gaps = line.gaps();
+        let justify = options.align == Align::Justify && !line.last && gaps > 0;
+        let extra = rect.width - line.width;
+        let mut gap = 0;
+        let mut after_word = false;
+
+        if !line.glyphs.is_empty() {
+            left = left.min(x);
+        }
+
+        for (i, glyph) in line.glyphs.iter().enumerate() {
+            placed.push(Placed { x, y, text: glyph.text, style: glyph.style });
+            x += glyph.width;
+            after_word |= !glyph.space;
+
+            // Indentation at the start of a paragraph isn't a gap
+            let gap_ends = glyph.space && after_word && line.glyphs.get(i + 1).is_some_and(|next| !next.space);
+            if justify && gap_ends {
+                let stretch = extra / gaps + if gap < extra % gaps { 1 } else { 0 };
+                for _ in 0..stretch {
+                    placed.push(Placed { x, y, text: " ", style: glyph.style });
+                    x += 1;
+                }
+                gap += 1;
+            }
+        }
+
+        if !line.glyphs.is_empty() {
+            right = right.max(x);
+        }
+    }
+
+    let bounds = if left <= right {
+        Rect::new(left, rect.y + top, right - left, count as i32)
+    } else {
+        Rect::new(rect.x, rect.y + top, 0, count as i32)
+    };
+
+    return (placed, bounds);
+}
+
+// Check if a grapheme cluster ends a line
+fn is_newline(grapheme: &str) -> bool {
+    return grapheme == "\n" || grapheme == "\r\n" || grapheme == "\r";
+}
+
+// Turn a grapheme cluster into a glyph, tabs become spaces and other control characters are dropped
+fn glyph<'a>(text: &'a str, style: Style) -> Option<Glyph<'a>> {
+    if text == "\t" {
+        return Some(Glyph { text: " ", style, width: 1, space: true });
+    }
+
+    let width = glyph_width(text);
+    if width == 0 {
+        return None;
+    }
+
+    return Some(Glyph { text, style, width, space: text.chars().all(char::is_whitespace) });
+}
+
+/* Wrap the glyphs of a paragraph into lines of the given width
+ *
+ * Lines are broken at the whitespace between words, which is dropped.
+ * Words longer than a line are broken between two grapheme clusters.
+*/
+fn wrap<'a>(glyphs: impl Iterator<Item = Glyph<'a>>, width: i32, lines: &mut Vec<Line<'a>>) {
+    let glyphs: Vec<Glyph> = glyphs.collect();
+    let mut line = Line::default();
+    let mut wrapped = false;
+
+    let mut start = 0;
+    while start < glyphs.len() {
+        // Take the next word, or the next run of whitespace
+        let space = glyphs[start].space;
+        let end = glyphs[start..]
+            .iter()
+            .position(|glyph| glyph.space != space)
+            .map_or(glyphs.len(), |length| start + length);
+        let piece = &glyphs[start..end];
+        let piece_width: i32 = piece.iter().map(|glyph| glyph.width).sum();
+        start = end;
+
+        if space {
+            // Whitespace at the start of a wrapped line is dropped
+            if !(wrapped && line.glyphs.is_empty()) {
+                piece.iter().for_each(|glyph| line.push(*glyph));
+            }
+            continue;
+        }
+
+        if line.width + piece_width > width && line.has_words() {
+            line.trim_end();
+            lines.push(std::mem::take(&mut line));
+            wrapped = true;
+        }
+        if line.width + piece_width > width {
+            // Only indentation is left in the line, and it doesn't leave room for the word
+            line.trim_end();
+        }
+
+        for glyph in piece {
+            if line.width + glyph.width > width && !line.glyphs.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                wrapped = true;
+            }
+
+            // A glyph wider than the whole line can't be shown at all
+            if glyph.width <= width {
+                line.push(*glyph);
+            }
+        }
+    }
+
+    line.trim_end();
+    line.last = true;
+    lines.push(line);
+}
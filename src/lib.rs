@@ -1,25 +1,79 @@
-use std::ops::Index;
+#![allow(clippy::needless_return)]
 
-#[cfg(target_os = "windows")]
-use windows::{self, Win32::System::Console};
+mod animation;
+mod backend;
+mod bitmap;
+mod border;
+mod braille;
+mod cell;
+mod color;
+mod error;
+mod export;
+mod figlet;
+mod image;
+mod layout;
+mod pixel;
+mod raster;
+mod rect;
+mod render;
+pub mod snapshot;
+mod span;
+mod sprite;
+pub mod terminal;
 
-pub struct BsCmdGraph {
+use std::collections::VecDeque;
+use std::path::Path;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+pub use animation::{Animation, AnimationMode, SpriteSheet};
+pub use backend::{console, Backend, DefaultBackend, HeadlessBackend, StdoutBackend, WriterBackend};
+#[cfg(windows)]
+pub use backend::WindowsBackend;
+pub use bitmap::{BitmapFont, BitmapGlyph, BitmapMode, BitmapOptions};
+pub use border::BorderStyle;
+pub use braille::BrailleCanvas;
+pub use cell::{Attributes, Cell, Style};
+pub use color::{Color, ColorDepth};
+pub use error::{BsGraphError, Result};
+pub use export::ExportOptions;
+pub use figlet::FigFont;
+pub use image::{Dither, Image, ImageMode, ImageOptions, Rgba};
+pub use layout::{Align, TextOptions, VAlign};
+pub use pixel::{PixelCanvas, PixelMode};
+pub use raster::{FillRule, PolyOptions};
+pub use rect::Rect;
+pub use span::{parse_markup, Span};
+pub use sprite::Sprite;
+use render::Renderer;
+pub use terminal::{Event, ResizePolicy, TerminalSession};
+
+pub struct BsCmdGraph<B: Backend = DefaultBackend> {
     width: i32,
     height: i32,
-    texture: i32,   // The character that will be used for the pixel
+    texture: char,  // The character that will be used for the pixel
 
-    display: [[i32; 100]; 100],
-    text_pointer: i32,
-    strings: [[i32; 100]; 100],
-    mask: [[i32; 100]; 100],
-    
-    #[cfg(target_os = "windows")]
-    handle: windows::Win32::Foundation::HANDLE,
+    // Row-major buffers of width * height cells
+    display: Vec<Cell>,
+    mask: Vec<i32>,
+
+    // Where the frames are drawn, and what is already there
+    backend: B,
+    renderer: Renderer,
+
+    // Resizing the display along with the backend
+    follow_size: bool,
+    resize_policy: ResizePolicy,
+
+    cell_aspect: f64,   // How many times taller than wide the cells look
+    resize_signals: bool,       // Whether resizes of the backend are announced by a signal
+    seen_resizes: usize,
+    events: VecDeque<Event>,
 }
 
 
 // Implement private methods for Bs_cmd_graph
-impl BsCmdGraph {
+impl<B: Backend> BsCmdGraph<B> {
     /* Check that the given coordinates are
      * within the boundaries of the display
      * Returns true if on boundaries, false otherwise
@@ -43,47 +97,360 @@ impl BsCmdGraph {
         
         // Check that both X and Y don't go below 0
         // or go above the limit of the display
-        return x > 0 && x < 100 && y > 0 && y < 100;
+        return x >= 0 && x < self.width && y >= 0 && y < self.height;
+    }
+
+    /* Convert (x, y) coordinates into an index of the row-major buffers
+     * The coordinates must already be within boundaries
+    */
+    fn index(&self, x: i32, y: i32) -> usize {
+        return (y * self.width + x) as usize;
+    }
+
+    /* Radii in cells of a circle of the given radius in columns
+     * The vertical radius is shortened by the aspect of the cells, so the circle looks round
+    */
+    fn circle_radii(&self, radius: i32) -> (i32, i32) {
+        let aspect = if self.cell_aspect > 0.0 { self.cell_aspect } else { 1.0 };
+        return (radius, (radius as f64 / aspect).round() as i32);
+    }
+
+    // Fail with OutOfBounds if the coordinates are outside of the display
+    fn require_boundaries(&self, x: i32, y: i32) -> Result<()> {
+        if !self.check_boundaries(x, y) {
+            return Err(BsGraphError::OutOfBounds { x, y });
+        }
+
+        return Ok(());
+    }
+
+    /* Draw the cells on the edges of a rectangle, in reading order
+     * `cell` is called with the position of every cell within the rectangle
+     * Fails with OutOfBounds on the first cell that was clipped
+    */
+    fn draw_outline<F: FnMut(i32, i32) -> Cell>(&mut self, rect: Rect, mut cell: F) -> Result<()> {
+        let mut result = Ok(());
+
+        for y in 0..rect.height.max(0) {
+            let edge = y == 0 || y == rect.height - 1;
+
+            for x in 0..rect.width {
+                if !edge && x != 0 && x != rect.width - 1 {
+                    continue;
+                }
+
+                let drawn = self.try_put_pixel(rect.x + x, rect.y + y, cell(x, y));
+                if result.is_ok() {
+                    result = drawn;
+                }
+            }
+        }
+
+        return result;
+    }
+
+    /* Lay out styled text inside of a rectangle and write it into the display
+     * Returns the area covered by the text, and OutOfBounds for the first
+     * character that was clipped
+    */
+    fn draw_layout(&mut self, text: &[(&str, Style)], rect: Rect, options: &TextOptions) -> (Rect, Result<()>) {
+        let (placed, bounds) = layout::layout(text, rect, options);
+        let mut result = Ok(());
+
+        for glyph in placed {
+            let width = layout::glyph_width(glyph.text);
+
+            if self.check_boundaries(glyph.x, glyph.y) && self.check_boundaries(glyph.x + width - 1, glyph.y) {
+                let cell = Cell::grapheme(glyph.text).with_style(glyph.style);
+                cell::place(&mut self.display, self.width, glyph.x, glyph.y, cell);
+            } else if result.is_ok() {
+                result = Err(BsGraphError::OutOfBounds { x: glyph.x, y: glyph.y });
+            }
+        }
+
+        return (bounds, result);
+    }
+
+    /* Resize the display if it follows the size of the backend and it changed
+     * An Event::Resize is queued for every change
+    */
+    fn check_resize(&mut self) {
+        if !self.follow_size {
+            return;
+        }
+
+        // Without signals there is no way to know, so the backend is always asked
+        if self.resize_signals {
+            let resizes = terminal::resize_count();
+            if resizes == self.seen_resizes {
+                return;
+            }
+            self.seen_resizes = resizes;
+        }
+
+        let Ok((width, height)) = self.backend.size() else {
+            return;
+        };
+
+        if width != self.width || height != self.height {
+            self.resize(width, height);
+            self.events.push_back(Event::Resize { width: self.width, height: self.height });
+        }
     }
 }
 
 
-// Implement public methods for Bs_cmd_graph
+// Constructors drawing on the terminal
 impl BsCmdGraph {
-    // Constructor taking in the window size and textur
+    /* Constructor taking in the window size and texture
+     * Panics if the size or the texture are invalid
+     * or there is no terminal to draw on, see try_new
+    */
     pub fn new(width: i32, height: i32, texture: i32) -> BsCmdGraph {
-        return BsCmdGraph {
-            width: width,
-            height: height,
-            texture: texture,
-
-            display: [[0; 100]; 100],           // Initialize empty display
-            text_pointer: 0,                    // Initialize empty text pointer
-            //strings: Vec::<String>::new(),      // Initialize empty strings vector
-            strings: [[0; 100]; 100],           // Initialize empty strings
-            mask: [[0; 100]; 100],           // Initialize empty masks
-
-            // TODO: Better handle this error
-            #[cfg(target_os = "windows")]
-            handle: unsafe {Console::GetStdHandle(Console::STD_OUTPUT_HANDLE)}.unwrap(),
-        };
+        match BsCmdGraph::try_new(width, height, texture) {
+            Ok(graph) => return graph,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    // Constructor taking in the window size and texture
+    pub fn try_new(width: i32, height: i32, texture: i32) -> Result<BsCmdGraph> {
+        return BsCmdGraph::try_with_backend(width, height, texture, BsCmdGraph::default_backend()?);
+    }
+
+    /* Constructor for a display covering the whole terminal
+     * The display is resized whenever the terminal is
+     *
+     * Panics if the texture is not a valid character
+     * or the size of the terminal is unknown, see try_new_fullscreen
+    */
+    pub fn new_fullscreen(texture: i32) -> BsCmdGraph {
+        match BsCmdGraph::try_new_fullscreen(texture) {
+            Ok(graph) => return graph,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /* Constructor for a display covering the whole terminal
+     * The display is resized whenever the terminal is
+    */
+    pub fn try_new_fullscreen(texture: i32) -> Result<BsCmdGraph> {
+        let backend = BsCmdGraph::default_backend()?;
+        let (width, height) = backend.size().map_err(|error| BsGraphError::TerminalUnavailable(error.to_string()))?;
+
+        let mut graph = BsCmdGraph::try_with_backend(width, height, texture, backend)?;
+        graph.set_follow_size(true);
+        return Ok(graph);
+    }
+
+    fn default_backend() -> Result<DefaultBackend> {
+        #[cfg(windows)]
+        return WindowsBackend::new();
+
+        #[cfg(not(windows))]
+        return Ok(StdoutBackend::new());
+    }
+}
+
+
+// Constructors drawing in memory
+impl BsCmdGraph<HeadlessBackend> {
+    /* Constructor for a display that is never shown, see HeadlessBackend
+     * Panics if the size or the texture are invalid, see try_with_backend
+    */
+    pub fn headless(width: i32, height: i32, texture: i32) -> BsCmdGraph<HeadlessBackend> {
+        return BsCmdGraph::with_backend(width, height, texture, HeadlessBackend::new(width, height));
+    }
+}
+
+
+// Implement public methods for Bs_cmd_graph
+impl<B: Backend> BsCmdGraph<B> {
+    /* Constructor taking in the window size, texture and where to draw
+     * Panics if the size or the texture are invalid, see try_with_backend
+    */
+    pub fn with_backend(width: i32, height: i32, texture: i32, backend: B) -> BsCmdGraph<B> {
+        match BsCmdGraph::try_with_backend(width, height, texture, backend) {
+            Ok(graph) => return graph,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /* Constructor taking in the window size, texture and where to draw
+     * Fails with InvalidGlyph if the texture is not a valid character,
+     * or InvalidSize if the display has more cells than an i32 can count
+    */
+    pub fn try_with_backend(width: i32, height: i32, texture: i32, backend: B) -> Result<BsCmdGraph<B>> {
+        let texture = char::from_u32(texture as u32).ok_or(BsGraphError::InvalidGlyph(texture as u32))?;
+
+        // Negative sizes make no sense, treat them as an empty display
+        let size = rect::area(width, height)?;
+        let width = width.max(0);
+        let height = height.max(0);
+
+        return Ok(BsCmdGraph {
+            width,
+            height,
+            texture,
+
+            display: vec![Cell::default(); size],   // Initialize empty display
+            mask: vec![0; size],                    // Initialize empty masks
+            backend,
+            renderer: Renderer::new(width, height), // Nothing has been drawn yet
+
+            follow_size: false,
+            resize_policy: ResizePolicy::default(),
+
+            cell_aspect: 2.0,
+            resize_signals: false,
+            seen_resizes: 0,
+            events: VecDeque::new(),
+        });
+    }
+
+    // Width of the display in cells
+    pub fn width(&self) -> i32 {
+        return self.width;
+    }
+
+    // Height of the display in cells
+    pub fn height(&self) -> i32 {
+        return self.height;
+    }
+
+    /* Make the display follow the size of the backend
+     * When the backend changes size, the display is resized following
+     * the resize policy and an Event::Resize is sent, see poll_event
+    */
+    pub fn set_follow_size(&mut self, follow: bool) {
+        self.follow_size = follow;
+
+        // Only terminals send resize signals, anything else is asked every time
+        self.resize_signals = follow && self.backend.watches_signals() && terminal::watch_resizes();
+        if self.resize_signals {
+            self.seen_resizes = terminal::resize_count();
+        }
+    }
+
+    // Choose what happens to the content of the display when it's resized
+    pub fn set_resize_policy(&mut self, policy: ResizePolicy) {
+        self.resize_policy = policy;
+    }
+
+    /* Tell how many times taller than wide the cells of the terminal look
+     * Circles and arcs are squashed by it so they come out round, the default is 2
+    */
+    pub fn set_cell_aspect(&mut self, aspect: f64) {
+        self.cell_aspect = aspect;
+    }
+
+    // How many times taller than wide the cells look, see set_cell_aspect
+    pub fn cell_aspect(&self) -> f64 {
+        return self.cell_aspect;
+    }
+
+    /* Change the size of the display
+     * The content is kept or cleared according to the resize policy,
+     * and the next cmd_draw redraws the whole display.
+     * Panics if the display would have more cells than an i32 can count
+    */
+    pub fn resize(&mut self, width: i32, height: i32) {
+        let size = rect::area(width, height).unwrap_or_else(|error| panic!("{}", error));
+        let width = width.max(0);
+        let height = height.max(0);
+
+        let mut display = vec![Cell::default(); size];
+        let mut mask = vec![0; size];
+
+        if self.resize_policy == ResizePolicy::Preserve {
+            for y in 0..height.min(self.height) {
+                for x in 0..width.min(self.width) {
+                    let old = self.index(x, y);
+                    let new = (y * width + x) as usize;
+
+                    display[new] = self.display[old].clone();
+                    mask[new] = self.mask[old];
+                }
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.display = display;
+        self.mask = mask;
+        self.renderer = Renderer::new(width, height);
+    }
+
+    /* Get the next thing that happened to the display, if any
+     * Checks whether the backend was resized first
+    */
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.check_resize();
+        return self.events.pop_front();
+    }
+
+    // Get a reference to the backend the display is drawn on
+    pub fn backend(&self) -> &B {
+        return &self.backend;
+    }
+
+    // Get a mutable reference to the backend the display is drawn on
+    pub fn backend_mut(&mut self) -> &mut B {
+        return &mut self.backend;
     }
     
-    /* Draw a single character to the screen at (x, y) coordinates
-     * The character will only be drawn if the
+    // Create a cell using the texture of the display in the given colour
+    pub fn texture_cell(&self, color: Color) -> Cell {
+        return Cell::new(self.texture).with_fg(color);
+    }
+
+    /* Draw a single cell to the screen at (x, y) coordinates
+     * The cell will only be drawn if the
      * coordinates are within the bounds of the window
      *
-     * Returns true if the character was successfully drawn, false otherwise
+     * Returns true if the cell was successfully drawn, false otherwise
+    */
+    pub fn put_pixel(&mut self, x: i32, y: i32, cell: Cell) -> bool {
+        return self.try_put_pixel(x, y, cell).is_ok();
+    }
+
+    /* Draw a single cell to the screen at (x, y) coordinates
+     * Fails with OutOfBounds if the coordinates are outside of the window
     */
-    pub fn put_pixel(&mut self, x: i32, y: i32, character: i32) -> bool {
+    pub fn try_put_pixel(&mut self, x: i32, y: i32, cell: Cell) -> Result<()> {
         // Check that the coordinates are within the boundaries
+        self.require_boundaries(x, y)?;
+
+        // Draw the cell to the screen
+        cell::place(&mut self.display, self.width, x, y, cell);
+        return Ok(());
+    }
+
+    /* Read back the cell at (x, y) coordinates
+     * Returns None if the coordinates are outside of the window
+    */
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Cell> {
         if !self.check_boundaries(x, y) {
-            return false;
+            return None;
         }
 
-        // Draw the character to the screen
-        self.display[x as usize][y as usize] = character;
-        return true;
+        return Some(self.display[self.index(x, y)].clone());
+    }
+    
+    /* Render the display into an image, as a terminal would show it
+     * Cells are drawn with the font and cell size of the options.
+     * Fails with InvalidSize if the cells are too large for an image of the whole display
+    */
+    pub fn to_image(&self, options: &ExportOptions) -> Result<Image> {
+        return export::render(&self.display, self.width, options);
+    }
+
+    /* Save the display to an image file, as PNG if its extension is .png and as PPM otherwise
+     * Fails with InvalidSize if the cells are too large, see to_image, with Io if it
+     * can't be written, or InvalidImage if PNG is asked for without the png feature
+    */
+    pub fn save_image<P: AsRef<Path>>(&self, path: P, options: &ExportOptions) -> Result<()> {
+        return self.to_image(options)?.save(path);
     }
     
     /* Draw a line to the screen between two points.
@@ -97,74 +464,36 @@ impl BsCmdGraph {
      * are within the boundaries of the window
      * Returns true if the line is successfully drawn, false otherwise
     */
+    #[allow(clippy::missing_safety_doc)]
     pub unsafe fn draw_line_old(&mut self) -> bool {
         todo!("Implement this deprecated function");
     } 
 
     /* Draw a line to the screen between two points
-     * The line will only be drawn if the coordinates of both points
-     * are within the boundaries of the window
+     * Only the parts of the line within the boundaries of the window are drawn
      *
-     * Returns true if the line is successfully drawn, false otherwise
+     * Returns true if the whole line was drawn, false if some of it was clipped
     */
-    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: i32)  -> bool {
-        let incyi;
-        let incyr;
-        let incxi;
-        let incxr;
+    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, cell: Cell)  -> bool {
+        return self.try_draw_line(x1, y1, x2, y2, cell).is_ok();
+    }
 
-        // Calcultate deltas between the points
-        let mut dx = x2 - x1;
-        let mut dy = y2 - y1;
-        
-        if dy >= 0 {
-            incyi = 1;
-        } else {
-            dy = dy * -1;
-            incyi = -1;
-        }
-        
-        if dx >= 0 {
-            incxi = 1;
-        } else {
-            dx = dx * -1;
-            incxi = -1;
-        }
-        
-        if dx >= dy {
-            incyr = 0;
-            incxr = incxi;
-        } else {
-            incxr = 0;
-            incyr = incyi;
-
-            // Invert dx and dy
-            dx ^= dy;
-            dy ^= dx;
-            dx ^= dy;
-        }
+    /* Draw a line to the screen between two points
+     * Only the parts of the line within the boundaries of the window are drawn
+     *
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, cell: Cell) -> Result<()> {
+        let mut result = Ok(());
 
-        let mut x = x1;
-        let mut y = y1;
-        let avr = 2 * dy;
-        let mut av = avr - dx;
-        let avi = av - dx;
-        
-        while x != x2 || y != y2 {
-            self.put_pixel(x, y, color);
-            
-            if av >= 0 {
-                x += incxi;
-                y += incyi;
-                av += avi;
-            } else {
-                x += incxr;
-                y += incyr;
-                av += avr;
+        raster::line(x1, y1, x2, y2, |x, y| {
+            let drawn = self.try_put_pixel(x, y, cell.clone());
+            if result.is_ok() {
+                result = drawn;
             }
-        }
-        
-        return true;
+        });
+
+        return result;
     }
     
     /* Draw a polygon to the screen
@@ -172,129 +501,672 @@ impl BsCmdGraph {
      *
      * Returns true if the polygon is fully drawn successfully, false otherwise
     */
-    pub fn draw_poly(&mut self, vertex_array: Vec<[i32;2]>, color: i32) -> bool {
-        let mut return_value = true;
+    pub fn draw_poly(&mut self, vertex_array: Vec<[i32;2]>, cell: Cell) -> bool {
+        return self.try_draw_poly(vertex_array, cell).is_ok();
+    }
+
+    /* Draw a polygon to the screen
+     * The polygon is represented as a vertex vector
+     *
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_draw_poly(&mut self, vertex_array: Vec<[i32;2]>, cell: Cell) -> Result<()> {
+        return self.try_draw_poly_with(vertex_array, cell, &PolyOptions::new());
+    }
+
+    /* Draw a polygon to the screen, closing it if the options say so
+     * The polygon is represented as a vertex vector
+     *
+     * Returns true if the polygon is fully drawn successfully, false otherwise
+    */
+    pub fn draw_poly_with(&mut self, vertex_array: Vec<[i32;2]>, cell: Cell, options: &PolyOptions) -> bool {
+        return self.try_draw_poly_with(vertex_array, cell, options).is_ok();
+    }
+
+    /* Draw a polygon to the screen, closing it if the options say so
+     * The polygon is represented as a vertex vector
+     *
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_draw_poly_with(&mut self, vertex_array: Vec<[i32;2]>, cell: Cell, options: &PolyOptions) -> Result<()> {
+        let mut result = Ok(());
+
+        raster::poly(&vertex_array, options.closed, |x, y| {
+            let drawn = self.try_put_pixel(x, y, cell.clone());
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
         
-        for vertex in vertex_array.windows(2) {
-            return_value = return_value &&
-            self.draw_line(
-                vertex[0][0],
-                vertex[0][1],
-                vertex[1][0],
-                vertex[1][1],
-                color
-            );
+        return result;
+    }
+
+    /* Draw a filled polygon to the screen, outline included
+     * The polygon is represented as a vertex vector, and it's always closed.
+     * It can be concave or cross itself, the fill rule of the options tells
+     * which parts of it are inside
+     *
+     * Returns true if the polygon is fully drawn successfully, false otherwise
+    */
+    pub fn fill_poly(&mut self, vertex_array: Vec<[i32;2]>, cell: Cell, options: &PolyOptions) -> bool {
+        return self.try_fill_poly(vertex_array, cell, options).is_ok();
+    }
+
+    /* Draw a filled polygon to the screen, see fill_poly
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_fill_poly(&mut self, vertex_array: Vec<[i32;2]>, cell: Cell, options: &PolyOptions) -> Result<()> {
+        let bounds = Rect::new(0, 0, self.width, self.height);
+        let clipped = raster::fill_poly(&vertex_array, options.fill_rule, bounds, |x, y| {
+            // Every point is within the display
+            cell::place(&mut self.display, self.width, x, y, cell.clone());
+        });
+
+        return match clipped {
+            Some((x, y)) => Err(BsGraphError::OutOfBounds { x, y }),
+            None => Ok(()),
+        };
+    }
+    
+    /* Draw the outline of a rectangle to the screen
+     * Returns true if the whole outline was drawn, false if some of it was clipped
+    */
+    pub fn draw_rect(&mut self, rect: Rect, cell: Cell) -> bool {
+        return self.try_draw_rect(rect, cell).is_ok();
+    }
+
+    /* Draw the outline of a rectangle to the screen
+     * Fails with OutOfBounds on the first cell that was clipped
+    */
+    pub fn try_draw_rect(&mut self, rect: Rect, cell: Cell) -> Result<()> {
+        return self.draw_outline(rect, |_, _| cell.clone());
+    }
+
+    /* Fill a rectangle of the screen with a cell
+     * Returns true if the whole rectangle was filled, false if some of it was clipped
+    */
+    pub fn fill_rect(&mut self, rect: Rect, cell: Cell) -> bool {
+        return self.try_fill_rect(rect, cell).is_ok();
+    }
+
+    /* Fill a rectangle of the screen with a cell
+     * Fails with OutOfBounds on the first cell that was clipped
+    */
+    pub fn try_fill_rect(&mut self, rect: Rect, cell: Cell) -> Result<()> {
+        let mut result = Ok(());
+
+        for y in rect.y..rect.bottom() {
+            for x in rect.x..rect.right() {
+                let drawn = self.try_put_pixel(x, y, cell.clone());
+                if result.is_ok() {
+                    result = drawn;
+                }
+            }
         }
-        
-        return return_value;
+
+        return result;
+    }
+
+    /* Draw a box to the screen, a rectangle outlined with box-drawing characters
+     * Only the border is drawn, whatever is inside of the box is kept
+     *
+     * Returns true if the whole border was drawn, false if some of it was clipped
+    */
+    pub fn draw_box(&mut self, rect: Rect, border: BorderStyle, style: Style) -> bool {
+        return self.try_draw_box(rect, border, style).is_ok();
+    }
+
+    /* Draw a box to the screen, see draw_box
+     * Fails with OutOfBounds on the first cell that was clipped
+    */
+    pub fn try_draw_box(&mut self, rect: Rect, border: BorderStyle, style: Style) -> Result<()> {
+        return self.draw_outline(rect, |x, y| {
+            return Cell::new(border.glyph_at(x, y, rect.width, rect.height)).with_style(style);
+        });
+    }
+
+    /* Draw a box to the screen with a title in its top border
+     * The title goes after the top left corner, with a space on each side,
+     * and it's cut if it doesn't fit between the corners
+     *
+     * Returns true if the whole box was drawn, false if some of it was clipped
+    */
+    pub fn draw_titled_box(&mut self, rect: Rect, border: BorderStyle, title: &str, style: Style) -> bool {
+        return self.try_draw_titled_box(rect, border, title, style).is_ok();
+    }
+
+    /* Draw a box to the screen with a title in its top border, see draw_titled_box
+     * Fails with OutOfBounds on the first cell that was clipped
+    */
+    pub fn try_draw_titled_box(&mut self, rect: Rect, border: BorderStyle, title: &str, style: Style) -> Result<()> {
+        let result = self.try_draw_box(rect, border, style);
+
+        let title = border::fit_title(title, rect.width - 4);
+        if !title.is_empty() && rect.height > 0 {
+            let drawn = self.try_draw_styled_text(rect.x + 1, rect.y, &format!(" {} ", title), style);
+            return result.and(drawn);
+        }
+
+        return result;
+    }
+
+    /* Draw the outline of a circle centred at (cx, cy)
+     * The radius is in columns, and the circle is squashed vertically by the cell aspect
+     *
+     * Returns true if the whole circle was drawn, false if some of it was clipped
+    */
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, cell: Cell) -> bool {
+        return self.try_draw_circle(cx, cy, radius, cell).is_ok();
+    }
+
+    /* Draw the outline of a circle centred at (cx, cy), see draw_circle
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_draw_circle(&mut self, cx: i32, cy: i32, radius: i32, cell: Cell) -> Result<()> {
+        let (rx, ry) = self.circle_radii(radius);
+        return self.try_draw_ellipse(cx, cy, rx, ry, cell);
+    }
+
+    /* Draw a filled circle centred at (cx, cy)
+     * The radius is in columns, and the circle is squashed vertically by the cell aspect
+     *
+     * Returns true if the whole circle was drawn, false if some of it was clipped
+    */
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, radius: i32, cell: Cell) -> bool {
+        return self.try_fill_circle(cx, cy, radius, cell).is_ok();
+    }
+
+    /* Draw a filled circle centred at (cx, cy), see fill_circle
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_fill_circle(&mut self, cx: i32, cy: i32, radius: i32, cell: Cell) -> Result<()> {
+        let (rx, ry) = self.circle_radii(radius);
+        return self.try_fill_ellipse(cx, cy, rx, ry, cell);
+    }
+
+    /* Draw the outline of an ellipse centred at (cx, cy)
+     * The radii are in cells, as they are, without correcting for the cell aspect
+     *
+     * Returns true if the whole ellipse was drawn, false if some of it was clipped
+    */
+    pub fn draw_ellipse(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, cell: Cell) -> bool {
+        return self.try_draw_ellipse(cx, cy, rx, ry, cell).is_ok();
+    }
+
+    /* Draw the outline of an ellipse centred at (cx, cy), see draw_ellipse
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_draw_ellipse(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, cell: Cell) -> Result<()> {
+        let mut result = Ok(());
+
+        raster::ellipse(cx, cy, rx, ry, |x, y| {
+            let drawn = self.try_put_pixel(x, y, cell.clone());
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
+
+        return result;
+    }
+
+    /* Draw a filled ellipse centred at (cx, cy)
+     * The radii are in cells, as they are, without correcting for the cell aspect
+     *
+     * Returns true if the whole ellipse was drawn, false if some of it was clipped
+    */
+    pub fn fill_ellipse(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, cell: Cell) -> bool {
+        return self.try_fill_ellipse(cx, cy, rx, ry, cell).is_ok();
+    }
+
+    /* Draw a filled ellipse centred at (cx, cy), see fill_ellipse
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_fill_ellipse(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, cell: Cell) -> Result<()> {
+        let mut result = Ok(());
+
+        raster::fill_ellipse(cx, cy, rx, ry, |x, y| {
+            let drawn = self.try_put_pixel(x, y, cell.clone());
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
+
+        return result;
+    }
+
+    /* Draw part of the outline of a circle centred at (cx, cy)
+     *
+     * Angles are in degrees, starting at the right of the circle and going
+     * counterclockwise, so 90 is the top. The arc goes counterclockwise from
+     * the start angle to the end angle, and it's the whole circle if they're
+     * 360 or more apart. The radius is in columns, as for draw_circle.
+     *
+     * Returns true if the whole arc was drawn, false if some of it was clipped
+    */
+    pub fn draw_arc(&mut self, cx: i32, cy: i32, radius: i32, start_angle: f64, end_angle: f64, cell: Cell) -> bool {
+        return self.try_draw_arc(cx, cy, radius, start_angle, end_angle, cell).is_ok();
+    }
+
+    /* Draw part of the outline of a circle centred at (cx, cy), see draw_arc
+     * Fails with OutOfBounds on the first point that was clipped
+    */
+    pub fn try_draw_arc(&mut self, cx: i32, cy: i32, radius: i32, start_angle: f64, end_angle: f64, cell: Cell) -> Result<()> {
+        let mut result = Ok(());
+        let (rx, ry) = self.circle_radii(radius);
+
+        raster::arc(cx, cy, rx, ry, start_angle, end_angle, |x, y| {
+            let drawn = self.try_put_pixel(x, y, cell.clone());
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
+
+        return result;
     }
     
-    // Draw an image to the screen
-    // Only parts of the image that are within boundaries will be drawn
-    pub fn draw_img(&mut self, image: [[i32;16];16], x: i32, y: i32) {
-        for (i, vertical) in image.iter().enumerate() {
-            for (j, horizontal) in vertical.iter().enumerate() {
-                if *horizontal != 0 {
-                    if self.check_boundaries(x, y) {
-                        self.display[j + x as usize][i + y as usize] = *horizontal;
+    // Draw a sprite to the screen with its top left corner at (x, y)
+    // Transparent cells of the sprite are left untouched
+    // Only parts of the sprite that are within boundaries will be drawn
+    //
+    // Returns true if the whole sprite was drawn, false if some of it was clipped
+    pub fn draw_img(&mut self, sprite: &Sprite, x: i32, y: i32) -> bool {
+        return self.try_draw_img(sprite, x, y).is_ok();
+    }
+
+    /* Draw a sprite to the screen with its top left corner at (x, y)
+     * Transparent cells of the sprite are left untouched
+     *
+     * Fails with OutOfBounds on the first cell of the sprite that was clipped
+    */
+    pub fn try_draw_img(&mut self, sprite: &Sprite, x: i32, y: i32) -> Result<()> {
+        let mut result = Ok(());
+
+        for i in 0..sprite.height() {
+            for j in 0..sprite.width() {
+                if let Some(cell) = sprite.get(j, i) {
+                    let drawn = self.try_put_pixel(x + j, y + i, cell.clone());
+                    if result.is_ok() {
+                        result = drawn;
                     }
                 }
             }
         }
+
+        return result;
+    }
+
+    // Draw a braille canvas to the screen with its top left cell at (x, y)
+    // Cells of the canvas without raised dots are left untouched,
+    // and the rest keep the background colour already on the screen
+    //
+    // Returns true if the whole canvas was drawn, false if some of it was clipped
+    pub fn draw_braille(&mut self, canvas: &BrailleCanvas, x: i32, y: i32) -> bool {
+        return self.try_draw_braille(canvas, x, y).is_ok();
+    }
+
+    /* Draw a braille canvas to the screen with its top left cell at (x, y), see draw_braille
+     * Fails with OutOfBounds on the first cell of the canvas that was clipped
+    */
+    pub fn try_draw_braille(&mut self, canvas: &BrailleCanvas, x: i32, y: i32) -> Result<()> {
+        let mut result = Ok(());
+
+        for row in 0..canvas.rows() {
+            for column in 0..canvas.columns() {
+                let Some((glyph, color)) = canvas.cell(column, row) else {
+                    continue;
+                };
+
+                let background = self.get_pixel(x + column, y + row).map_or(Color::Default, |cell| cell.bg);
+                let drawn = self.try_put_pixel(x + column, y + row, Cell::new(glyph).with_fg(color).with_bg(background));
+                if result.is_ok() {
+                    result = drawn;
+                }
+            }
+        }
+
+        return result;
+    }
+
+    // Draw a pixel canvas to the screen with its top left cell at (x, y)
+    // Cells of the canvas without pixels are left untouched, and cells
+    // with a single colour keep the background colour already on the screen
+    //
+    // Returns true if the whole canvas was drawn, false if some of it was clipped
+    pub fn draw_pixels(&mut self, canvas: &PixelCanvas, x: i32, y: i32) -> bool {
+        return self.try_draw_pixels(canvas, x, y).is_ok();
+    }
+
+    /* Draw a pixel canvas to the screen with its top left cell at (x, y), see draw_pixels
+     * Fails with OutOfBounds on the first cell of the canvas that was clipped
+    */
+    pub fn try_draw_pixels(&mut self, canvas: &PixelCanvas, x: i32, y: i32) -> Result<()> {
+        let mut result = Ok(());
+
+        for row in 0..canvas.rows() {
+            for column in 0..canvas.columns() {
+                let Some((glyph, foreground, background)) = canvas.cell(column, row) else {
+                    continue;
+                };
+
+                let background = background.unwrap_or_else(|| self.get_pixel(x + column, y + row).map_or(Color::Default, |cell| cell.bg));
+                let drawn = self.try_put_pixel(x + column, y + row, Cell::new(glyph).with_fg(foreground).with_bg(background));
+                if result.is_ok() {
+                    result = drawn;
+                }
+            }
+        }
+
+        return result;
+    }
+
+    // Draw the frame of a sprite sheet an animation is showing right now
+    // The frame is drawn like any other sprite, with its top left corner at (x, y)
+    //
+    // Returns true if the whole frame was drawn, false if some of it was clipped
+    pub fn draw_animation(&mut self, sheet: &SpriteSheet, animation: &Animation, x: i32, y: i32) -> bool {
+        return self.try_draw_animation(sheet, animation, x, y).is_ok();
+    }
+
+    /* Draw the frame of a sprite sheet an animation is showing right now
+     * Nothing is drawn if the animation has no frames or the sheet lacks the frame
+     *
+     * Fails with OutOfBounds on the first cell of the frame that was clipped
+    */
+    pub fn try_draw_animation(&mut self, sheet: &SpriteSheet, animation: &Animation, x: i32, y: i32) -> Result<()> {
+        return match animation.current_sprite(sheet) {
+            Some(sprite) => self.try_draw_img(sprite, x, y),
+            None => Ok(()),
+        };
+    }
+
+
+    // Draw an image scaled to fill a rectangle of the screen
+    // The options pick between a character ramp, coloured half blocks or braille dots
+    // Transparent parts of the image are left untouched
+    //
+    // Returns true if the whole image was drawn, false if some of it was clipped
+    pub fn draw_image(&mut self, image: &Image, rect: Rect, options: &ImageOptions) -> bool {
+        return self.try_draw_image(image, rect, options).is_ok();
+    }
+
+    /* Draw an image scaled to fill a rectangle of the screen, see draw_image
+     * Fails with OutOfBounds on the first cell that was clipped
+    */
+    pub fn try_draw_image(&mut self, image: &Image, rect: Rect, options: &ImageOptions) -> Result<()> {
+        let sprite = image.render(rect.width, rect.height, options);
+        return self.try_draw_img(&sprite, rect.x, rect.y);
     }
     
     // Draw text to the screen
-    // The text is drawn in the specified coordinates
+    // The text is drawn in the specified coordinates, one grapheme cluster per cell
+    // Wide characters take two cells and zero width characters are skipped
     // Only parts of the text that are within boundaries will be drawn
-    pub fn draw_text(&mut self, x: i32, y: i32, text: &str) {
-        // Generate an ID for the display to know which text to draw
-        let spot = self.text_pointer + 257;
-        let text = text.to_owned() + "\n";  // Append newline to text
-        
-        // This places the text where it needs to be drawn
-        self.display[x as usize][y as usize] = spot;
-        
-        let mut i = 0;
-        while i < text.len() {
-            self.strings[i][self.text_pointer as usize] = text.as_bytes()[i].into();
-            i += 1;
-        }
+    //
+    // Returns true if the whole text was drawn, false if some of it was clipped
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str) -> bool {
+        return self.try_draw_text(x, y, text).is_ok();
+    }
+
+    /* Draw text to the screen in the default colours
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_text(&mut self, x: i32, y: i32, text: &str) -> Result<()> {
+        return self.try_draw_text_with(x, y, text, |_, _| Style::default());
+    }
+
+    // Draw text to the screen in the given style
+    // Returns true if the whole text was drawn, false if some of it was clipped
+    pub fn draw_styled_text(&mut self, x: i32, y: i32, text: &str, style: Style) -> bool {
+        return self.try_draw_text_with(x, y, text, |_, _| style).is_ok();
+    }
+
+    /* Draw text to the screen in the given style
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_styled_text(&mut self, x: i32, y: i32, text: &str, style: Style) -> Result<()> {
+        return self.try_draw_text_with(x, y, text, |_, _| style);
+    }
+
+    /* Draw text to the screen, styling every character on its own
+     * `style` is called with the index and the grapheme cluster of each character
+     *
+     * Returns true if the whole text was drawn, false if some of it was clipped
+    */
+    pub fn draw_text_with<F>(&mut self, x: i32, y: i32, text: &str, style: F) -> bool
+    where
+        F: FnMut(usize, &str) -> Style,
+    {
+        return self.try_draw_text_with(x, y, text, style).is_ok();
+    }
+
+    /* Draw text to the screen, styling every character on its own
+     *
+     * The text is written into the display right away, so anything drawn
+     * later goes over it. Characters outside of the boundaries are left out,
+     * including wide characters that only fit halfway.
+     *
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_text_with<F>(&mut self, x: i32, y: i32, text: &str, mut style: F) -> Result<()>
+    where
+        F: FnMut(usize, &str) -> Style,
+    {
+        let mut result = Ok(());
+        let mut column = x;
+
+        for (i, grapheme) in text.graphemes(true).enumerate() {
+            // Control characters don't take any space on the screen
+            let width = layout::glyph_width(grapheme);
+            if width == 0 {
+                continue;
+            }
+
+            if self.check_boundaries(column, y) && self.check_boundaries(column + width - 1, y) {
+                let cell = Cell::grapheme(grapheme).with_style(style(i, grapheme));
+                cell::place(&mut self.display, self.width, column, y, cell);
+            } else if result.is_ok() {
+                result = Err(BsGraphError::OutOfBounds { x: column, y });
+            }
 
-        let mut i = 1;
-        while i < text.len()-1 {
-            self.display[(x+1) as usize][y as usize] = -1;
-            i += 1;
+            column += width;
         }
 
-        self.text_pointer += 1;
+        return result;
     }
 
-    // Draw the final display buffer to the console
-    pub fn cmd_draw(&mut self) {
-        let mut ptr;
-
-        for y in 0..self.height-1 {
-            for x in 0..self.width-1 {
-                match self.display[x as usize][y as usize] {
-                    0 => {
-                        #[cfg(target_os = "linux")]
-                        print!("\x1b[49m");
-                        
-                        print!(" ");
-                    },
-                    -1 => continue,
-                    _ => {
-                        if self.display[x as usize][y as usize] > 256 {
-                            ptr = self.display[x as usize][y as usize] - 257;
-                            
-                            for i in 0..99 {
-                                if self.strings[i as usize][ptr as usize] == '\n' as i32 {
-                                    break;
-                                }
-                                // TODO: Handle this error
-                                print!("{}", char::from_u32(self.strings[i as usize][ptr as usize] as u32).unwrap());
-                            }
-                        }
-
-                        else {
-                            #[cfg(target_os = "windows")]
-                            {
-                                Console::SetConsoleTextAttribute(self.handle, self.display[x as usize][y as usize]);
-                                print!(tex);
-                            }
-                            
-                            #[cfg(target_os = "linux")]
-                            print!("\x1b[{}m", self.texture);
-                        }
-                        
-                        break;
-                    },
+    /* Draw a block of text inside of a rectangle
+     *
+     * The text is split in lines at every newline, and long lines are
+     * wrapped and aligned as the options say. Text that doesn't fit in the
+     * rectangle is cut, and parts of the rectangle outside of the window
+     * are clipped.
+     *
+     * Returns the area covered by the text
+    */
+    pub fn draw_text_block(&mut self, rect: Rect, text: &str, options: &TextOptions) -> Rect {
+        return self.draw_layout(&layout::graphemes(text, Style::default()), rect, options).0;
+    }
+
+    /* Draw a block of text inside of a rectangle, see draw_text_block
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_text_block(&mut self, rect: Rect, text: &str, options: &TextOptions) -> Result<Rect> {
+        let (bounds, result) = self.draw_layout(&layout::graphemes(text, Style::default()), rect, options);
+        return result.map(|_| bounds);
+    }
+
+    /* Draw styled text inside of a rectangle
+     * The spans are laid out together, like the text of draw_text_block
+     *
+     * Returns the area covered by the text
+    */
+    pub fn draw_spans(&mut self, rect: Rect, spans: &[Span], options: &TextOptions) -> Rect {
+        return self.draw_layout(&span::graphemes(spans), rect, options).0;
+    }
+
+    /* Draw styled text inside of a rectangle, see draw_spans
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_spans(&mut self, rect: Rect, spans: &[Span], options: &TextOptions) -> Result<Rect> {
+        let (bounds, result) = self.draw_layout(&span::graphemes(spans), rect, options);
+        return result.map(|_| bounds);
+    }
+
+    /* Draw text with inline markup inside of a rectangle
+     * See parse_markup for the tags, and draw_text_block for the layout
+     *
+     * Returns the area covered by the text
+    */
+    pub fn draw_markup(&mut self, rect: Rect, text: &str, options: &TextOptions) -> Rect {
+        return self.draw_spans(rect, &parse_markup(text), options);
+    }
+
+    /* Draw text with inline markup inside of a rectangle, see draw_markup
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_markup(&mut self, rect: Rect, text: &str, options: &TextOptions) -> Result<Rect> {
+        return self.try_draw_spans(rect, &parse_markup(text), options);
+    }
+
+    /* Draw banner text in large letters of a FIGlet font
+     * The top left corner of the banner is at (x, y), and every line of
+     * the text takes as many rows as the font is tall. Blanks in the letters
+     * are transparent, and parts of the banner outside of the window are clipped
+     *
+     * Returns true if the whole banner was drawn, false if some of it was clipped
+    */
+    pub fn draw_banner(&mut self, x: i32, y: i32, text: &str, font: &FigFont) -> bool {
+        return self.try_draw_styled_banner(x, y, text, font, Style::default()).is_ok();
+    }
+
+    /* Draw banner text in large letters of a FIGlet font, see draw_banner
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_banner(&mut self, x: i32, y: i32, text: &str, font: &FigFont) -> Result<()> {
+        return self.try_draw_styled_banner(x, y, text, font, Style::default());
+    }
+
+    // Draw banner text in large letters of a FIGlet font in the given style, see draw_banner
+    // Returns true if the whole banner was drawn, false if some of it was clipped
+    pub fn draw_styled_banner(&mut self, x: i32, y: i32, text: &str, font: &FigFont, style: Style) -> bool {
+        return self.try_draw_styled_banner(x, y, text, font, style).is_ok();
+    }
+
+    /* Draw banner text in large letters of a FIGlet font in the given style
+     * Fails with OutOfBounds on the first character that was clipped
+    */
+    pub fn try_draw_styled_banner(&mut self, x: i32, y: i32, text: &str, font: &FigFont, style: Style) -> Result<()> {
+        let mut result = Ok(());
+
+        for (i, row) in font.render(text).iter().enumerate() {
+            for (j, character) in row.chars().enumerate() {
+                if character == ' ' {
+                    continue;
+                }
+
+                let drawn = self.try_put_pixel(x + j as i32, y + i as i32, Cell::new(character).with_style(style));
+                if result.is_ok() {
+                    result = drawn;
                 }
             }
+        }
 
-            println!();
-        }   
+        return result;
     }
-    
-    // Clear the console screen
-    pub fn cmd_clear(&mut self) {
-        // Clear the screen and place the cursor at position 1
-        print!("\x1B[2J\x1B[1;1H");
-        //print!("\x1bc");
-        //std::process::Command::new("clear").status().unwrap();
-        
-        // TODO: Find a better fix
-        self.text_pointer = 0;
-        
-        for i in 0..self.height-1 {
-            for j in 0..self.width-1 {
-                self.put_pixel(j, i, 0);
-                self.mask[j as usize][i as usize] = 0;
+
+    /* Draw text in large letters of a bitmap font
+     * The top left corner of the text is at (x, y), and the options say how
+     * big the pixels of the font are and how they are drawn. Unset pixels
+     * are transparent, and parts of the text outside of the window are clipped
+     *
+     * Returns true if the whole text was drawn, false if some of it was clipped
+    */
+    pub fn draw_bitmap_text(&mut self, x: i32, y: i32, text: &str, font: &BitmapFont, options: &BitmapOptions) -> bool {
+        return self.try_draw_bitmap_text(x, y, text, font, options).is_ok();
+    }
+
+    /* Draw text in large letters of a bitmap font, see draw_bitmap_text
+     * Fails with OutOfBounds on the first cell that was clipped
+    */
+    pub fn try_draw_bitmap_text(&mut self, x: i32, y: i32, text: &str, font: &BitmapFont, options: &BitmapOptions) -> Result<()> {
+        let pixels = font.render(text);
+        let scale = options.scale.max(1) as usize;
+        let width = pixels.first().map_or(0, |row| row.len()) * scale;
+        let height = pixels.len() * scale;
+
+        // Pixel of the scaled up text
+        let pixel = |px: usize, py: usize| -> bool {
+            return py < height && pixels[py / scale][px / scale];
+        };
+
+        let mut result = Ok(());
+        let mut draw = |graph: &mut BsCmdGraph<B>, cx: usize, cy: usize, glyph: char| {
+            let cell = Cell::new(glyph).with_style(options.style);
+            let drawn = graph.try_put_pixel(x + cx as i32, y + cy as i32, cell);
+            if result.is_ok() {
+                result = drawn;
             }
+        };
+
+        match options.mode {
+            BitmapMode::Fill(fill) => {
+                for py in 0..height {
+                    for px in (0..width).filter(|px| pixel(*px, py)) {
+                        draw(self, px, py, fill);
+                    }
+                }
+            },
+            BitmapMode::HalfBlock => {
+                for cy in 0..height.div_ceil(2) {
+                    for px in 0..width {
+                        let glyph = match (pixel(px, cy * 2), pixel(px, cy * 2 + 1)) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => continue,
+                        };
+                        draw(self, px, cy, glyph);
+                    }
+                }
+            },
         }
+
+        return result;
     }
-}
 
+    /* Draw the final display buffer to the backend
+     * Panics if the backend fails, see try_cmd_draw
+    */
+    pub fn cmd_draw(&mut self) {
+        if let Err(error) = self.try_cmd_draw() {
+            panic!("{}", error);
+        }
+    }
+
+    /* Draw the final display buffer to the backend
+     * Only the cells that changed since the last call are sent to the backend,
+     * and the whole frame is written at once to avoid flickering
+     *
+     * Fails with BackendIo if the backend can't write the frame
+    */
+    pub fn try_cmd_draw(&mut self) -> Result<()> {
+        self.check_resize();
+
+        self.renderer.render(&self.display, &mut self.backend)?;
+        return Ok(());
+    }
+    
+    /* Clear the display buffer
+     * The backend itself is only updated by the next cmd_draw,
+     * which avoids showing an empty screen in between frames
+    */
+    pub fn cmd_clear(&mut self) {
+        self.check_resize();
+
+        self.display.fill(Cell::default());
+        self.mask.fill(0);
+    }
+}
 
 /*
 void Bs_cmd_graph::Bs_cmd_clear(){ // we define here the action to clear screen for refresh
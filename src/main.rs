@@ -1,22 +1,22 @@
-use bsgraphlib;
 use std::thread;
 use std::time;
 
 fn main() {
-    let mut screen = bsgraphlib::BsCmdGraph::new(50, 50, '#' as i32);
+    let mut screen = bsgraphlib::BsCmdGraph::new_fullscreen('#' as i32);
     
     loop {
         screen.cmd_clear();
 
-        screen.draw_text(15, 15, "Hello, World!");
-        screen.draw_text(15, 20, "It works!!!");
+        // Keep the text in the middle of the terminal, even after it's resized
+        let x = screen.width() / 2 - 6;
+        let y = screen.height() / 2 - 2;
+
+        screen.draw_text(x, y, "Hello, World!");
+        screen.draw_text(x, y + 3, "It works!!!");
 
         screen.cmd_draw();
         
-        /* FIXME: If you don't add this delay, the screen breaks
-         * Find a way to make this work without stopping execution */
-        thread::sleep(time::Duration::from_millis(5));
+        // Limit the demo to roughly 60 frames per second
+        thread::sleep(time::Duration::from_millis(16));
     }
 }
-
-// TODO: Write tests
@@ -0,0 +1,257 @@
+/* Pixel canvas
+ *
+ * Block characters split a cell in smaller pixels: half blocks in two
+ * pixels one above the other, quadrants in 2 by 2 and sextants in 2 by 3.
+ * A pixel canvas draws coloured pixels in one of these modes. As a cell
+ * only has a foreground and a background colour, it can show at most two
+ * colours, so pixels of any other colour take the second most used one.
+*/
+
+use crate::{raster, rect, BsGraphError, Color, Result};
+
+// Half blocks by the pixels they fill, top then bottom
+const HALF_BLOCKS: [char; 4] = [' ', '▀', '▄', '█'];
+
+// Quadrants by the pixels they fill, in reading order
+const QUADRANTS: [char; 16] = [' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█'];
+
+// First of the sextants of the Symbols for Legacy Computing block
+const SEXTANTS: u32 = 0x1fb00;
+
+// How the cells of a pixel canvas are split in pixels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PixelMode {
+    // Two pixels per cell, one above the other
+    #[default]
+    HalfBlock,
+    // 2 by 2 pixels per cell
+    Quadrant,
+    // 2 by 3 pixels per cell
+    Sextant,
+}
+
+impl PixelMode {
+    // Number of columns and rows of pixels of every cell
+    pub fn cell_size(self) -> (i32, i32) {
+        return match self {
+            PixelMode::HalfBlock => (1, 2),
+            PixelMode::Quadrant => (2, 2),
+            PixelMode::Sextant => (2, 3),
+        };
+    }
+
+    // Character filling the pixels of a cell set in the mask, in reading order
+    fn glyph(self, mask: u8) -> char {
+        return match self {
+            PixelMode::HalfBlock => HALF_BLOCKS[mask as usize & 3],
+            PixelMode::Quadrant => QUADRANTS[mask as usize & 15],
+            PixelMode::Sextant => match mask & 63 {
+                // These sextants were already in Unicode as other block characters
+                0 => ' ',
+                21 => '▌',
+                42 => '▐',
+                63 => '█',
+                mask => char::from_u32(SEXTANTS + mask as u32 - 1 - (mask > 21) as u32 - (mask > 42) as u32).unwrap(),
+            },
+        };
+    }
+}
+
+// A grid of coloured pixels, drawn on the display as block characters
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PixelCanvas {
+    columns: i32,
+    rows: i32,
+    mode: PixelMode,
+    pixels: Vec<Option<Color>>,     // Row-major, None for pixels that aren't set
+}
+
+impl PixelCanvas {
+    /* Create an empty canvas of the given size in cells
+     * Panics if the canvas has more pixels than an i32 can count
+    */
+    pub fn new(columns: i32, rows: i32, mode: PixelMode) -> PixelCanvas {
+        let (cell_width, cell_height) = mode.cell_size();
+        let pixels = columns.checked_mul(cell_width).zip(rows.checked_mul(cell_height)).map(|(width, height)| rect::area(width, height));
+        let Some(Ok(size)) = pixels else {
+            panic!("{}", BsGraphError::InvalidSize { width: columns, height: rows });
+        };
+
+        return PixelCanvas {
+            columns: columns.max(0),
+            rows: rows.max(0),
+            mode,
+            pixels: vec![None; size],
+        };
+    }
+
+    // How the cells are split in pixels
+    pub fn mode(&self) -> PixelMode {
+        return self.mode;
+    }
+
+    // Width of the canvas in cells
+    pub fn columns(&self) -> i32 {
+        return self.columns;
+    }
+
+    // Height of the canvas in cells
+    pub fn rows(&self) -> i32 {
+        return self.rows;
+    }
+
+    // Width of the canvas in pixels
+    pub fn width(&self) -> i32 {
+        return self.columns * self.mode.cell_size().0;
+    }
+
+    // Height of the canvas in pixels
+    pub fn height(&self) -> i32 {
+        return self.rows * self.mode.cell_size().1;
+    }
+
+    // Unset every pixel of the canvas
+    pub fn clear(&mut self) {
+        self.pixels.fill(None);
+    }
+
+    /* Set the pixel at (x, y) to a colour
+     * Returns true if the pixel is within the canvas, false otherwise
+    */
+    pub fn put_pixel(&mut self, x: i32, y: i32, color: Color) -> bool {
+        return self.try_put_pixel(x, y, color).is_ok();
+    }
+
+    /* Set the pixel at (x, y) to a colour
+     * Fails with OutOfBounds if the pixel is outside of the canvas
+    */
+    pub fn try_put_pixel(&mut self, x: i32, y: i32, color: Color) -> Result<()> {
+        let index = self.index(x, y).ok_or(BsGraphError::OutOfBounds { x, y })?;
+
+        self.pixels[index] = Some(color);
+        return Ok(());
+    }
+
+    /* Unset the pixel at (x, y)
+     * Returns true if the pixel is within the canvas, false otherwise
+    */
+    pub fn unset_pixel(&mut self, x: i32, y: i32) -> bool {
+        let Some(index) = self.index(x, y) else {
+            return false;
+        };
+
+        self.pixels[index] = None;
+        return true;
+    }
+
+    // Colour of the pixel at (x, y), None if it isn't set or it's outside of the canvas
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Color> {
+        return self.pixels[self.index(x, y)?];
+    }
+
+    /* Draw a line of pixels between two points
+     * Returns true if the whole line was drawn, false if some of it was clipped
+    */
+    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: Color) -> bool {
+        return self.try_draw_line(x1, y1, x2, y2, color).is_ok();
+    }
+
+    /* Draw a line of pixels between two points
+     * Fails with OutOfBounds on the first pixel that was clipped
+    */
+    pub fn try_draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: Color) -> Result<()> {
+        let mut result = Ok(());
+
+        raster::line(x1, y1, x2, y2, |x, y| {
+            let drawn = self.try_put_pixel(x, y, color);
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
+
+        return result;
+    }
+
+    /* Draw a polygon of pixels, represented as a vertex vector
+     * Returns true if the whole polygon was drawn, false if some of it was clipped
+    */
+    pub fn draw_poly(&mut self, vertex_array: Vec<[i32; 2]>, color: Color) -> bool {
+        return self.try_draw_poly(vertex_array, color).is_ok();
+    }
+
+    /* Draw a polygon of pixels, represented as a vertex vector
+     * Fails with OutOfBounds on the first pixel that was clipped
+    */
+    pub fn try_draw_poly(&mut self, vertex_array: Vec<[i32; 2]>, color: Color) -> Result<()> {
+        let mut result = Ok(());
+
+        raster::poly(&vertex_array, false, |x, y| {
+            let drawn = self.try_put_pixel(x, y, color);
+            if result.is_ok() {
+                result = drawn;
+            }
+        });
+
+        return result;
+    }
+
+    /* The block character and colours of the cell at (column, row)
+     *
+     * The character is drawn in the foreground colour, and the rest of the
+     * cell takes the background colour if there is one. Without it the
+     * pixels that aren't set should keep whatever is below them.
+     * Returns None for cells without any pixels set, or outside of the canvas
+    */
+    pub fn cell(&self, column: i32, row: i32) -> Option<(char, Color, Option<Color>)> {
+        if column < 0 || column >= self.columns || row < 0 || row >= self.rows {
+            return None;
+        }
+
+        let (cell_width, cell_height) = self.mode.cell_size();
+        let pixels: Vec<Option<Color>> = (0..cell_height)
+            .flat_map(|y| (0..cell_width).map(move |x| (column * cell_width + x, row * cell_height + y)))
+            .map(|(x, y)| self.get_pixel(x, y))
+            .collect();
+
+        // The most used colour is the foreground, the first one to appear if there's a tie
+        let mut counts: Vec<(Color, usize)> = Vec::new();
+        for color in pixels.iter().flatten() {
+            match counts.iter_mut().find(|(counted, _)| counted == color) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*color, 1)),
+            }
+        }
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let foreground = counts.first()?.0;
+        let background = counts.get(1).map(|(color, _)| *color);
+        let mask = pixels.iter().enumerate().filter(|(_, pixel)| **pixel == Some(foreground)).fold(0, |mask, (i, _)| mask | 1 << i);
+
+        return Some((self.mode.glyph(mask), foreground, background));
+    }
+}
+
+// Implement private methods for PixelCanvas
+impl PixelCanvas {
+    // Index of (x, y) in the pixels, if it's inside of the canvas
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || x >= self.width() || y < 0 || y >= self.height() {
+            return None;
+        }
+
+        return Some((y * self.width() + x) as usize);
+    }
+}
+
+/* Pixels filled by a block character, as the columns and rows it's split
+ * in and a mask of the pixels in reading order
+ * Returns None for anything that isn't a half block, quadrant or sextant
+*/
+pub(crate) fn block_pixels(character: char) -> Option<(i32, i32, u8)> {
+    if let Some(mask) = QUADRANTS.iter().position(|quadrant| *quadrant == character).filter(|mask| *mask != 0) {
+        return Some((2, 2, mask as u8));
+    }
+
+    let sextant = (character as u32).checked_sub(SEXTANTS).filter(|sextant| *sextant < 60)? as u8 + 1;
+    return Some((2, 3, sextant + (sextant >= 21) as u8 + (sextant >= 41) as u8));
+}
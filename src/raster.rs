@@ -0,0 +1,340 @@
+/* Rasterisation of shapes
+ *
+ * The algorithms here only work out which points make up a shape, and
+ * hand every one of them to `plot`. That way the same lines and polygons
+ * can be drawn on cells, on braille dots or on any other grid of points.
+*/
+
+use crate::Rect;
+
+// Which parts of a polygon that crosses itself count as inside of it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FillRule {
+    // Inside if a ray from the point crosses the outline an odd number of times
+    #[default]
+    EvenOdd,
+    // Inside if the outline winds around the point at all
+    NonZero,
+}
+
+/* How a polygon is drawn
+ *
+ * Outlines are open paths by default, so the last vertex isn't joined
+ * back to the first one. Filled polygons are always closed, and use the
+ * fill rule to tell the inside from the outside.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PolyOptions {
+    pub closed: bool,
+    pub fill_rule: FillRule,
+}
+
+impl PolyOptions {
+    // Open outlines, filled with the even-odd rule
+    pub fn new() -> PolyOptions {
+        return PolyOptions::default();
+    }
+
+    // Return a copy of the options that joins the last vertex back to the first one
+    pub fn with_closed(mut self, closed: bool) -> PolyOptions {
+        self.closed = closed;
+        return self;
+    }
+
+    // Return a copy of the options with a different fill rule
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> PolyOptions {
+        self.fill_rule = fill_rule;
+        return self;
+    }
+}
+
+// An edge of a polygon in the active edge table
+struct Edge {
+    y_max: i32,     // First row below the edge
+    x: f64,         // Where the edge crosses the current row
+    slope: f64,     // How much x moves from one row to the next
+    winding: i32,   // 1 if the edge goes down, -1 if it goes up
+}
+
+/* Plot a line between two points, using the Bresenham algorithm
+ * Every point of the line is plotted once
+*/
+pub(crate) fn line<F: FnMut(i32, i32)>(x1: i32, y1: i32, x2: i32, y2: i32, mut plot: F) {
+    let incyi;
+    let incyr;
+    let incxi;
+    let incxr;
+
+    // Calcultate deltas between the points
+    let mut dx = x2 - x1;
+    let mut dy = y2 - y1;
+
+    if dy >= 0 {
+        incyi = 1;
+    } else {
+        dy = -dy;
+        incyi = -1;
+    }
+
+    if dx >= 0 {
+        incxi = 1;
+    } else {
+        dx = -dx;
+        incxi = -1;
+    }
+
+    if dx >= dy {
+        incyr = 0;
+        incxr = incxi;
+    } else {
+        incxr = 0;
+        incyr = incyi;
+
+        // Invert dx and dy
+        std::mem::swap(&mut dx, &mut dy);
+    }
+
+    let mut x = x1;
+    let mut y = y1;
+    let avr = 2 * dy;
+    let mut av = avr - dx;
+    let avi = av - dx;
+
+    while x != x2 || y != y2 {
+        plot(x, y);
+
+        if av >= 0 {
+            x += incxi;
+            y += incyi;
+            av += avi;
+        } else {
+            x += incxr;
+            y += incyr;
+            av += avr;
+        }
+    }
+
+    // The loop stops on the last point, so it's plotted here, which also covers lines of a single point
+    plot(x2, y2);
+}
+
+/* Plot the lines between every vertex and the next one
+ * Closed polygons also get a line from the last vertex back to the first one
+*/
+pub(crate) fn poly<F: FnMut(i32, i32)>(vertices: &[[i32; 2]], closed: bool, mut plot: F) {
+    for vertex in vertices.windows(2) {
+        line(vertex[0][0], vertex[0][1], vertex[1][0], vertex[1][1], &mut plot);
+    }
+
+    if let (true, Some(first), Some(last)) = (closed, vertices.first(), vertices.last()) {
+        if first != last {
+            line(last[0], last[1], first[0], first[1], &mut plot);
+        }
+    }
+}
+
+/* Plot a polygon and everything inside of it, using a scanline fill
+ *
+ * Every row is tested against the edges that cross it, kept in the active
+ * edge table. An edge counts from its top row up to the one above its
+ * bottom row, so vertices where the outline goes on down aren't counted
+ * twice. The spans between the crossings are inside or outside depending
+ * on the fill rule. The outline is plotted too, so the fill covers the
+ * same points as the closed outline does.
+ *
+ * Only the points within `bounds` are plotted, each of them once, so the
+ * work follows the visible part of the polygon rather than all of it.
+ * Returns the first point of the outline outside of the bounds, if any.
+ * Whenever some of the inside is clipped, so is some of the outline
+*/
+pub(crate) fn fill_poly<F: FnMut(i32, i32)>(vertices: &[[i32; 2]], rule: FillRule, bounds: Rect, mut plot: F) -> Option<(i32, i32)> {
+    let rows = vertices.iter().map(|vertex| vertex[1]);
+    let (Some(top), Some(bottom)) = (rows.clone().min(), rows.max()) else {
+        return None;
+    };
+
+    // Points of the outline by row, as only the visible rows are filled
+    let mut clipped = None;
+    let mut outline: Vec<Vec<i32>> = vec![Vec::new(); bounds.height.max(0) as usize];
+    poly(vertices, true, |x, y| {
+        if bounds.contains(x, y) {
+            outline[(y - bounds.y) as usize].push(x);
+        } else if clipped.is_none() {
+            clipped = Some((x, y));
+        }
+    });
+
+    // Edge table, sorted by the first row each edge crosses. Horizontal
+    // edges are already covered by the outline, so they're left out
+    let mut edges: Vec<(i32, Edge)> = Vec::new();
+    for (i, from) in vertices.iter().enumerate() {
+        let to = vertices[(i + 1) % vertices.len()];
+        if from[1] == to[1] {
+            continue;
+        }
+
+        let (top, bottom, winding) = if from[1] < to[1] { (*from, to, 1) } else { (to, *from, -1) };
+        let slope = (bottom[0] as f64 - top[0] as f64) / (bottom[1] as f64 - top[1] as f64);
+
+        edges.push((top[1], Edge {
+            y_max: bottom[1],
+            x: top[0] as f64,
+            slope,
+            winding,
+        }));
+    }
+    edges.sort_by_key(|(y_min, _)| std::cmp::Reverse(*y_min));
+
+    let mut active: Vec<Edge> = Vec::new();
+    let mut row: Vec<i32> = Vec::new();
+    for y in top.max(bounds.y)..=bottom.min(bounds.bottom() - 1) {
+        // Edges that started above the bounds join at the row where they cross them
+        while edges.last().is_some_and(|(y_min, _)| *y_min <= y) {
+            let (y_min, mut edge) = edges.pop().unwrap();
+            edge.x += edge.slope * (y as f64 - y_min as f64);
+            active.push(edge);
+        }
+        active.retain(|edge| edge.y_max > y);
+        active.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+        // Fill between the crossings where the rule says the row is inside
+        row.clear();
+        row.append(&mut outline[(y - bounds.y) as usize]);
+        let mut winding = 0;
+        for (i, edge) in active.iter().enumerate() {
+            let was_inside = match rule {
+                FillRule::EvenOdd => i % 2 == 1,
+                FillRule::NonZero => winding != 0,
+            };
+            winding += edge.winding;
+
+            if was_inside {
+                let start = active[i - 1].x.ceil().max(bounds.x as f64) as i32;
+                let end = edge.x.floor().min(bounds.right() as f64 - 1.0) as i32;
+                row.extend(start..=end);
+            }
+        }
+
+        // The outline and the fill meet, so the row is only plotted once
+        row.sort_unstable();
+        row.dedup();
+        for x in &row {
+            plot(*x, y);
+        }
+
+        for edge in active.iter_mut() {
+            edge.x += edge.slope;
+        }
+    }
+
+    return clipped;
+}
+
+/* Plot the outline of an ellipse with the given radii, using the midpoint algorithm
+ * Every point of the outline is plotted once
+*/
+pub(crate) fn ellipse<F: FnMut(i32, i32)>(cx: i32, cy: i32, rx: i32, ry: i32, mut plot: F) {
+    for (x, y) in ellipse_points(rx, ry) {
+        plot(cx + x, cy + y);
+    }
+}
+
+// Plot an ellipse with the given radii and everything inside of it
+pub(crate) fn fill_ellipse<F: FnMut(i32, i32)>(cx: i32, cy: i32, rx: i32, ry: i32, mut plot: F) {
+    if rx < 0 || ry < 0 {
+        return;
+    }
+
+    // Half the width of every row, from the middle one down to the edge
+    let mut spans = vec![0; ry as usize + 1];
+    for (x, y) in quarter(rx, ry) {
+        spans[y as usize] = spans[y as usize].max(x);
+    }
+
+    for y in -ry..=ry {
+        let span = spans[y.unsigned_abs() as usize];
+        for x in -span..=span {
+            plot(cx + x, cy + y);
+        }
+    }
+}
+
+/* Plot the part of the outline of an ellipse between two angles, in degrees
+ *
+ * Angles start at the right of the ellipse and go counterclockwise, as
+ * seen on the screen, so 90 is the top. The arc goes counterclockwise from
+ * `start` to `end`, and it's the whole ellipse if they're 360 or more apart.
+*/
+pub(crate) fn arc<F: FnMut(i32, i32)>(cx: i32, cy: i32, rx: i32, ry: i32, start: f64, end: f64, mut plot: F) {
+    let full = (end - start).abs() >= 360.0;
+    let sweep = (end - start).rem_euclid(360.0);
+
+    for (x, y) in ellipse_points(rx, ry) {
+        // Angle of the point on a circle the ellipse is a stretched copy of
+        let angle = (-y as f64 / ry.max(1) as f64).atan2(x as f64 / rx.max(1) as f64).to_degrees();
+
+        if full || (angle - start).rem_euclid(360.0) <= sweep {
+            plot(cx + x, cy + y);
+        }
+    }
+}
+
+/* Points of a quarter of an ellipse centred at the origin, going clockwise
+ * from the top (0, ry) down to the right (rx, 0), as y grows downwards
+*/
+fn quarter(rx: i32, ry: i32) -> Vec<(i32, i32)> {
+    // Flat ellipses are just lines
+    if rx == 0 || ry == 0 {
+        return (0..=ry).rev().map(|y| (0, y)).chain((1..=rx).map(|x| (x, 0))).collect();
+    }
+
+    let mut points = Vec::new();
+    let (a2, b2) = (rx as i64 * rx as i64, ry as i64 * ry as i64);
+    let (mut x, mut y) = (0i64, ry as i64);
+
+    // Decision values are multiplied by 4 to keep them whole numbers
+
+    // Where the slope is less than 1, x moves every step
+    let mut d = 4 * b2 - 4 * a2 * y + a2;
+    while b2 * x <= a2 * y {
+        points.push((x as i32, y as i32));
+
+        if d >= 0 {
+            d += 4 * a2 * (2 - 2 * y);
+            y -= 1;
+        }
+        d += 4 * b2 * (2 * x + 3);
+        x += 1;
+    }
+
+    // Where the slope is more than 1, y moves every step
+    let mut d = b2 * (2 * x + 1) * (2 * x + 1) + 4 * a2 * (y - 1) * (y - 1) - 4 * a2 * b2;
+    while y >= 0 {
+        points.push((x as i32, y as i32));
+
+        if d <= 0 {
+            d += 4 * b2 * (2 * x + 2);
+            x += 1;
+        }
+        d += 4 * a2 * (3 - 2 * y);
+        y -= 1;
+    }
+
+    return points;
+}
+
+// Points of the outline of an ellipse centred at the origin, without repeating any
+fn ellipse_points(rx: i32, ry: i32) -> Vec<(i32, i32)> {
+    if rx < 0 || ry < 0 {
+        return Vec::new();
+    }
+
+    let mut points: Vec<(i32, i32)> = quarter(rx, ry)
+        .into_iter()
+        .flat_map(|(x, y)| [(x, -y), (-x, -y), (x, y), (-x, y)])
+        .collect();
+
+    points.sort_by_key(|(x, y)| (*y, *x));
+    points.dedup();
+    return points;
+}
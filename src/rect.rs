@@ -0,0 +1,46 @@
+use crate::{BsGraphError, Result};
+
+// A rectangular area of the display, in cells
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    // Create a rectangle from its top left corner and its size
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        return Rect { x, y, width, height };
+    }
+
+    // The first column to the right of the rectangle
+    pub fn right(&self) -> i32 {
+        return self.x + self.width;
+    }
+
+    // The first row below the rectangle
+    pub fn bottom(&self) -> i32 {
+        return self.y + self.height;
+    }
+
+    // Check if the rectangle doesn't cover any cell
+    pub fn is_empty(&self) -> bool {
+        return self.width <= 0 || self.height <= 0;
+    }
+
+    // Check if the cell at (x, y) is inside of the rectangle
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        return x >= self.x && x < self.right() && y >= self.y && y < self.bottom();
+    }
+}
+
+/* Number of cells of a row-major buffer of the given size, negative sizes being empty
+ * Cells are found by their i32 coordinates, so a buffer can't hold more
+ * of them than an i32 can count. Fails with InvalidSize for larger sizes
+*/
+pub(crate) fn area(width: i32, height: i32) -> Result<usize> {
+    let cells = width.max(0).checked_mul(height.max(0)).ok_or(BsGraphError::InvalidSize { width, height })?;
+    return Ok(cells as usize);
+}
@@ -0,0 +1,59 @@
+use std::io;
+
+use crate::{rect, Backend, Cell};
+
+/* Incremental renderer for the console
+ *
+ * It keeps a copy of the last frame that was sent to the backend,
+ * so every new frame only has to send the cells that changed since then.
+*/
+pub(crate) struct Renderer {
+    width: i32,
+    height: i32,
+    previous: Vec<Cell>,
+    invalidated: bool,
+}
+
+impl Renderer {
+    pub(crate) fn new(width: i32, height: i32) -> Renderer {
+        return Renderer {
+            width,
+            height,
+            previous: vec![Cell::default(); rect::area(width, height).unwrap_or_else(|error| panic!("{}", error))],
+            invalidated: true,
+        };
+    }
+
+    /* Send the cells that changed since the last frame to the backend
+     * `frame` must be a row-major buffer of the same size as the renderer
+    */
+    pub(crate) fn render<B: Backend>(&mut self, frame: &[Cell], backend: &mut B) -> io::Result<()> {
+        // Start from a cleared output, which is the same as a frame of empty cells
+        if self.invalidated {
+            backend.clear()?;
+            self.previous.fill(Cell::default());
+            self.invalidated = false;
+        }
+
+        let mut changes = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = (y * self.width + x) as usize;
+
+                if frame[index] != self.previous[index] {
+                    changes.push((x, y, &frame[index]));
+                }
+            }
+        }
+
+        if !changes.is_empty() {
+            backend.draw(&changes)?;
+
+            // Leave the cursor below the display
+            backend.move_cursor(0, self.height)?;
+        }
+
+        self.previous.clone_from_slice(frame);
+        return backend.flush();
+    }
+}
@@ -0,0 +1,130 @@
+/* Helpers to compare the output of a HeadlessBackend against golden files
+ *
+ * A snapshot is the plain text of the backend, followed by a map of the
+ * styles when any cell is not in the default style:
+ *
+ *     Hello
+ *     --- styles
+ *     aaaaa
+ *     a: fg red, bold
+ *
+ * Trailing spaces are trimmed from every line so the files are easy to edit.
+ * Set the BSGRAPH_UPDATE_SNAPSHOTS environment variable to write the current
+ * output to the golden files instead of comparing against them.
+*/
+
+use std::fs;
+use std::path::Path;
+
+use crate::{Cell, Color, HeadlessBackend};
+
+// Environment variable that turns assertions into updates of the golden files
+pub const UPDATE_VARIABLE: &str = "BSGRAPH_UPDATE_SNAPSHOTS";
+
+// Render the content of the backend in the snapshot format
+pub fn render(backend: &HeadlessBackend) -> String {
+    let mut snapshot = String::new();
+
+    for line in backend.lines() {
+        snapshot += line.trim_end();
+        snapshot.push('\n');
+    }
+
+    let (map, legend) = backend.style_map();
+    if !legend.is_empty() {
+        snapshot += "--- styles\n";
+
+        for line in map {
+            snapshot += line.trim_end();
+            snapshot.push('\n');
+        }
+
+        for (key, style) in legend {
+            snapshot += &format!("{}: {}\n", key, describe_style(&style));
+        }
+    }
+
+    return snapshot;
+}
+
+/* Compare the content of the backend against the golden file at `path`
+ * Returns a description of the differences if they don't match
+*/
+pub fn compare(backend: &HeadlessBackend, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    let actual = render(backend);
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(error) => return Err(format!("can't read snapshot {}: {}", path.display(), error)),
+    };
+
+    // Golden files might have been checked out with Windows line endings
+    let expected = expected.replace("\r\n", "\n");
+    if expected == actual {
+        return Ok(());
+    }
+
+    let mut report = format!("snapshot {} doesn't match:\n", path.display());
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        let expected_line = expected_lines.get(i).copied();
+        let actual_line = actual_lines.get(i).copied();
+
+        if expected_line != actual_line {
+            report += &format!("{:>4} - {}\n", i + 1, expected_line.unwrap_or("<missing>"));
+            report += &format!("{:>4} + {}\n", i + 1, actual_line.unwrap_or("<missing>"));
+        }
+    }
+
+    return Err(report);
+}
+
+/* Assert that the content of the backend matches the golden file at `path`
+ * When BSGRAPH_UPDATE_SNAPSHOTS is set, the golden file is written instead
+ *
+ * Panics with the differences if they don't match
+*/
+pub fn assert_snapshot(backend: &HeadlessBackend, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+
+    if std::env::var_os(UPDATE_VARIABLE).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, render(backend)).unwrap();
+        return;
+    }
+
+    if let Err(report) = compare(backend, path) {
+        panic!("{}\n(set {} to update the snapshot)", report, UPDATE_VARIABLE);
+    }
+}
+
+// Describe the colours and attributes of a style, like "fg red, bg #0000ff, bold"
+fn describe_style(style: &Cell) -> String {
+    let mut parts = Vec::new();
+
+    if style.fg != Color::Default {
+        parts.push(format!("fg {}", style.fg));
+    }
+    if style.bg != Color::Default {
+        parts.push(format!("bg {}", style.bg));
+    }
+    if style.attrs.bold {
+        parts.push("bold".to_owned());
+    }
+    if style.attrs.italic {
+        parts.push("italic".to_owned());
+    }
+    if style.attrs.underline {
+        parts.push("underline".to_owned());
+    }
+    if style.attrs.reverse {
+        parts.push("reverse".to_owned());
+    }
+
+    return parts.join(", ");
+}
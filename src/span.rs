@@ -0,0 +1,139 @@
+/* Styled text
+ *
+ * Text in more than one style is written as a list of spans, each one a
+ * piece of text in a single style. Spans can be built by hand or parsed
+ * from text with inline markup, and are drawn like any other text block.
+*/
+
+use std::str::FromStr;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Color, Style};
+
+// A piece of text drawn in a single style
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    // Create a span of text in the given style
+    pub fn new(text: &str, style: Style) -> Span {
+        return Span {
+            text: text.to_owned(),
+            style,
+        };
+    }
+
+    // Create a span of text in the default colours
+    pub fn raw(text: &str) -> Span {
+        return Span::new(text, Style::default());
+    }
+}
+
+impl From<&str> for Span {
+    fn from(text: &str) -> Span {
+        return Span::raw(text);
+    }
+}
+
+/* Parse text with inline markup into spans
+ *
+ * Tags in square brackets open a style, which lasts until the matching [/]:
+ *   "[red]error[/] in [bold]main[/]"
+ *
+ * A tag holds any of bold (b), italic (i), underline (u) and reverse,
+ * a foreground colour, and "on" followed by a background colour,
+ * e.g. "[bold bright_white on #0000aa]". Colours are written as for
+ * Color::from_str, with underscores instead of spaces.
+ *
+ * Tags can be nested, and "[[" stands for a literal bracket.
+ * Anything that isn't a valid tag is kept as text.
+*/
+pub fn parse_markup(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut styles = vec![Style::default()];
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let style = *styles.last().unwrap();
+
+        // Plain text up to the next bracket
+        let end = rest.find('[').unwrap_or(rest.len());
+        push(&mut spans, &rest[..end], style);
+        rest = &rest[end..];
+
+        if rest.is_empty() {
+            break;
+        }
+        if let Some(after) = rest.strip_prefix("[[") {
+            push(&mut spans, "[", style);
+            rest = after;
+            continue;
+        }
+
+        let tag = rest.find(']').map(|close| &rest[1..close]);
+        match (tag, tag.and_then(|tag| parse_tag(tag, style))) {
+            (Some("/"), _) => {
+                // The default style at the bottom is never closed
+                if styles.len() > 1 {
+                    styles.pop();
+                }
+            },
+            (Some(_), Some(opened)) => styles.push(opened),
+            _ => {
+                // Not a tag after all, keep the bracket and go on after it
+                push(&mut spans, "[", style);
+                rest = &rest[1..];
+                continue;
+            },
+        }
+
+        rest = &rest[tag.unwrap().len() + 2..];
+    }
+
+    return spans;
+}
+
+// Split spans in grapheme clusters, each one with the style of its span
+pub(crate) fn graphemes(spans: &[Span]) -> Vec<(&str, Style)> {
+    return spans
+        .iter()
+        .flat_map(|span| span.text.graphemes(true).map(move |grapheme| (grapheme, span.style)))
+        .collect();
+}
+
+// Add text to the spans, joining it to the last span if it has the same style
+fn push(spans: &mut Vec<Span>, text: &str, style: Style) {
+    if text.is_empty() {
+        return;
+    }
+
+    match spans.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(text),
+        _ => spans.push(Span::new(text, style)),
+    }
+}
+
+/* Apply the contents of a tag to a style
+ * Returns None if the tag is empty or has anything it doesn't understand
+*/
+fn parse_tag(tag: &str, mut style: Style) -> Option<Style> {
+    let mut words = tag.split_whitespace().peekable();
+    words.peek()?;
+
+    while let Some(word) = words.next() {
+        match word.to_ascii_lowercase().as_str() {
+            "bold" | "b" => style.attrs.bold = true,
+            "italic" | "i" => style.attrs.italic = true,
+            "underline" | "u" => style.attrs.underline = true,
+            "reverse" => style.attrs.reverse = true,
+            "on" => style.bg = Color::from_str(words.next()?).ok()?,
+            color => style.fg = Color::from_str(color).ok()?,
+        }
+    }
+
+    return Some(style);
+}
@@ -0,0 +1,225 @@
+use crate::{rect, BsGraphError, Cell, Result};
+
+/* An image made of cells, of any size
+ *
+ * Every cell of a sprite is either opaque or transparent. Transparent
+ * cells leave whatever is below them untouched when the sprite is drawn.
+ * They come from an explicit mask, from a key cell that marks them, or
+ * from cells that were never set.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sprite {
+    width: i32,
+    height: i32,
+
+    // Row-major buffers of width * height cells
+    cells: Vec<Cell>,
+    opaque: Vec<bool>,
+}
+
+impl Sprite {
+    /* Create a sprite of the given size where every cell is transparent
+     * Panics if the sprite has more cells than an i32 can count
+    */
+    pub fn new(width: i32, height: i32) -> Sprite {
+        let size = rect::area(width, height).unwrap_or_else(|error| panic!("{}", error));
+
+        return Sprite {
+            width: width.max(0),
+            height: height.max(0),
+            cells: vec![Cell::default(); size],
+            opaque: vec![false; size],
+        };
+    }
+
+    /* Create a sprite from its cells in row-major order, all of them opaque
+     * Fails with InvalidSize if the size is too large, or SizeMismatch
+     * if there aren't width * height cells
+    */
+    pub fn from_cells(width: i32, height: i32, cells: Vec<Cell>) -> Result<Sprite> {
+        rect::area(width, height)?;
+
+        let mut sprite = Sprite::new(width, height);
+        if cells.len() != sprite.cells.len() {
+            return Err(BsGraphError::SizeMismatch { expected: sprite.cells.len(), actual: cells.len() });
+        }
+
+        sprite.cells = cells;
+        sprite.opaque.fill(true);
+        return Ok(sprite);
+    }
+
+    /* Create a sprite from lines of text, one character per cell
+     * Cells holding the key character are transparent, and so are
+     * the cells missing at the end of lines shorter than the longest one
+    */
+    pub fn from_text(text: &str, key: char) -> Sprite {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+
+        let mut sprite = Sprite::new(width as i32, lines.len() as i32);
+        for (y, line) in lines.iter().enumerate() {
+            for (x, character) in line.chars().enumerate() {
+                if character != key {
+                    sprite.set(x as i32, y as i32, Cell::new(character));
+                }
+            }
+        }
+
+        return sprite;
+    }
+
+    // Return the sprite with every cell equal to the key made transparent
+    pub fn with_key(mut self, key: &Cell) -> Sprite {
+        for (cell, opaque) in self.cells.iter().zip(self.opaque.iter_mut()) {
+            if cell == key {
+                *opaque = false;
+            }
+        }
+
+        return self;
+    }
+
+    /* Return the sprite with a mask of which cells are opaque, in row-major order
+     * Fails with SizeMismatch if the mask doesn't have width * height values
+    */
+    pub fn with_mask(mut self, mask: Vec<bool>) -> Result<Sprite> {
+        if mask.len() != self.opaque.len() {
+            return Err(BsGraphError::SizeMismatch { expected: self.opaque.len(), actual: mask.len() });
+        }
+
+        self.opaque = mask;
+        return Ok(self);
+    }
+
+    // Width of the sprite in cells
+    pub fn width(&self) -> i32 {
+        return self.width;
+    }
+
+    // Height of the sprite in cells
+    pub fn height(&self) -> i32 {
+        return self.height;
+    }
+
+    /* Get the cell at (x, y)
+     * Returns None if it's transparent or outside of the sprite
+    */
+    pub fn get(&self, x: i32, y: i32) -> Option<&Cell> {
+        let index = self.index(x, y)?;
+        if !self.opaque[index] {
+            return None;
+        }
+
+        return Some(&self.cells[index]);
+    }
+
+    /* Set the cell at (x, y), which becomes opaque
+     * Returns false if it's outside of the sprite
+    */
+    pub fn set(&mut self, x: i32, y: i32, cell: Cell) -> bool {
+        let Some(index) = self.index(x, y) else {
+            return false;
+        };
+
+        self.cells[index] = cell;
+        self.opaque[index] = true;
+        return true;
+    }
+
+    /* Make the cell at (x, y) transparent
+     * Returns false if it's outside of the sprite
+    */
+    pub fn clear(&mut self, x: i32, y: i32) -> bool {
+        let Some(index) = self.index(x, y) else {
+            return false;
+        };
+
+        self.opaque[index] = false;
+        return true;
+    }
+
+    /* Copy a rectangle of the sprite into a new sprite
+     * Parts of the rectangle outside of the sprite are transparent
+    */
+    pub fn crop(&self, x: i32, y: i32, width: i32, height: i32) -> Sprite {
+        let mut sprite = Sprite::new(width, height);
+
+        for i in 0..sprite.height {
+            for j in 0..sprite.width {
+                if let Some(cell) = self.get(x + j, y + i) {
+                    sprite.set(j, i, cell.clone());
+                }
+            }
+        }
+
+        return sprite;
+    }
+
+    // Return the sprite mirrored from left to right
+    pub fn flip_horizontal(self) -> Sprite {
+        let (width, height) = (self.width, self.height);
+        return self.transform(width, height, |x, y| (width - 1 - x, y));
+    }
+
+    // Return the sprite mirrored from top to bottom
+    pub fn flip_vertical(self) -> Sprite {
+        let (width, height) = (self.width, self.height);
+        return self.transform(width, height, |x, y| (x, height - 1 - y));
+    }
+
+    /* Return the sprite rotated clockwise by the given number of quarter turns
+     * Negative turns rotate it counterclockwise
+    */
+    pub fn rotate(self, quarter_turns: i32) -> Sprite {
+        let (width, height) = (self.width, self.height);
+
+        // Every function maps a cell of the rotated sprite back to the original one
+        return match quarter_turns.rem_euclid(4) {
+            1 => self.transform(height, width, |x, y| (y, height - 1 - x)),
+            2 => self.transform(width, height, |x, y| (width - 1 - x, height - 1 - y)),
+            3 => self.transform(height, width, |x, y| (width - 1 - y, x)),
+            _ => self,
+        };
+    }
+}
+
+// Implement private methods for Sprite
+impl Sprite {
+    // Index of (x, y) in the row-major buffers, if it's inside of the sprite
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || x >= self.width || y < 0 || y >= self.height {
+            return None;
+        }
+
+        return Some((y * self.width + x) as usize);
+    }
+
+    /* Build a sprite of the given size
+     * `source` maps every cell of the new sprite to the cell of this one it comes from
+    */
+    fn transform<F: Fn(i32, i32) -> (i32, i32)>(self, width: i32, height: i32, source: F) -> Sprite {
+        let mut sprite = Sprite::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (source_x, source_y) = source(x, y);
+                let from = self.index(source_x, source_y).unwrap();
+                let to = (y * width + x) as usize;
+
+                sprite.cells[to] = self.cells[from].clone();
+                sprite.opaque[to] = self.opaque[from];
+            }
+        }
+
+        return sprite;
+    }
+}
+
+impl From<[[Cell; 16]; 16]> for Sprite {
+    // Convert an image of the old fixed size, where empty cells are transparent
+    fn from(image: [[Cell; 16]; 16]) -> Sprite {
+        let cells: Vec<Cell> = image.into_iter().flatten().collect();
+        return Sprite::from_cells(16, 16, cells).unwrap().with_key(&Cell::default());
+    }
+}
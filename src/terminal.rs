@@ -0,0 +1,317 @@
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::panic;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+
+// Things that happened to the display, see BsCmdGraph::poll_event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    // The display was resized to follow the terminal
+    Resize { width: i32, height: i32 },
+}
+
+// What happens to the content of the display when it's resized
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResizePolicy {
+    // Keep the cells that still fit in the new size
+    #[default]
+    Preserve,
+    // Start over with an empty display
+    Clear,
+}
+
+/* Size of the terminal as (width, height)
+ *
+ * The terminal is looked up through the standard output, input and error,
+ * in that order, and falls back to the COLUMNS and LINES variables
+ * when none of them is a terminal.
+*/
+pub fn size() -> io::Result<(i32, i32)> {
+    if let Some(size) = query_size() {
+        return Ok(size);
+    }
+
+    let read = |name: &str| std::env::var(name).ok().and_then(|value| value.parse::<i32>().ok());
+    if let (Some(width), Some(height)) = (read("COLUMNS"), read("LINES")) {
+        return Ok((width, height));
+    }
+
+    return Err(io::Error::other("the size of the terminal is unknown"));
+}
+
+#[cfg(unix)]
+fn query_size() -> Option<(i32, i32)> {
+    for fd in [libc::STDOUT_FILENO, libc::STDIN_FILENO, libc::STDERR_FILENO] {
+        let mut window: libc::winsize = unsafe { std::mem::zeroed() };
+
+        if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut window) } == 0 && window.ws_col > 0 && window.ws_row > 0 {
+            return Some((window.ws_col as i32, window.ws_row as i32));
+        }
+    }
+
+    return None;
+}
+
+#[cfg(windows)]
+fn query_size() -> Option<(i32, i32)> {
+    use ::windows::Win32::System::Console;
+
+    let handle = unsafe { Console::GetStdHandle(Console::STD_OUTPUT_HANDLE) }.ok()?;
+    let mut info = Console::CONSOLE_SCREEN_BUFFER_INFO::default();
+    if !unsafe { Console::GetConsoleScreenBufferInfo(handle, &mut info) }.as_bool() {
+        return None;
+    }
+
+    let window = info.srWindow;
+    return Some(((window.Right - window.Left + 1) as i32, (window.Bottom - window.Top + 1) as i32));
+}
+
+#[cfg(not(any(unix, windows)))]
+fn query_size() -> Option<(i32, i32)> {
+    return None;
+}
+
+// Number of SIGWINCH signals received since the handler was installed
+#[cfg(unix)]
+static RESIZES: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(unix)]
+extern "C" fn on_resize(_: libc::c_int) {
+    RESIZES.fetch_add(1, Ordering::Relaxed);
+}
+
+/* Start counting the resizes of the terminal
+ *
+ * Returns false when resizes can't be watched, because the platform has no
+ * resize signal or the application already handles it. The size then has
+ * to be checked again every time instead.
+*/
+#[cfg(unix)]
+pub(crate) fn watch_resizes() -> bool {
+    use std::sync::OnceLock;
+
+    static WATCHING: OnceLock<bool> = OnceLock::new();
+
+    return *WATCHING.get_or_init(|| unsafe {
+        // Don't take the signal away from a handler installed by someone else
+        let mut current: libc::sigaction = std::mem::zeroed();
+        if libc::sigaction(libc::SIGWINCH, std::ptr::null(), &mut current) != 0 || current.sa_sigaction != libc::SIG_DFL {
+            return false;
+        }
+
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_resize as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+
+        return libc::sigaction(libc::SIGWINCH, &action, std::ptr::null_mut()) == 0;
+    });
+}
+
+#[cfg(not(unix))]
+pub(crate) fn watch_resizes() -> bool {
+    return false;
+}
+
+// Number of resizes seen so far, only meaningful when watch_resizes returned true
+#[cfg(unix)]
+pub(crate) fn resize_count() -> usize {
+    return RESIZES.load(Ordering::Relaxed);
+}
+
+#[cfg(not(unix))]
+pub(crate) fn resize_count() -> usize {
+    return 0;
+}
+
+// Escape sequences to set up the terminal for drawing and to undo it
+const ENTER_SEQUENCE: &str = "\x1b[?1049h\x1b[?25l";
+const LEAVE_SEQUENCE: &str = "\x1b[0m\x1b[?25h\x1b[?1049l";
+
+// What has to be restored when the session ends
+struct SavedState {
+    #[cfg(unix)]
+    termios: Option<libc::termios>,
+
+    #[cfg(windows)]
+    output_mode: Option<::windows::Win32::System::Console::CONSOLE_MODE>,
+    #[cfg(windows)]
+    input_mode: Option<::windows::Win32::System::Console::CONSOLE_MODE>,
+}
+
+// The state of the active session, shared with the panic hook
+static SESSION: Mutex<Option<SavedState>> = Mutex::new(None);
+
+/* Guard that prepares the terminal for drawing while it's alive
+ *
+ * It switches to the alternate screen, so the scrollback of the user
+ * is left untouched, and hides the cursor. Raw mode can be enabled too,
+ * which stops the terminal from echoing and buffering the input.
+ *
+ * Everything is restored when the guard is dropped, and also when the
+ * program panics, before the panic message is printed.
+ * Only one session can be active at a time.
+*/
+pub struct TerminalSession {
+    // Sessions belong to the thread that created them
+    _not_send: PhantomData<*const ()>,
+}
+
+impl TerminalSession {
+    // Start a session on the alternate screen with a hidden cursor
+    pub fn new() -> io::Result<TerminalSession> {
+        return TerminalSession::start(false);
+    }
+
+    // Start a session on the alternate screen with a hidden cursor and raw input
+    pub fn with_raw_mode() -> io::Result<TerminalSession> {
+        return TerminalSession::start(true);
+    }
+
+    fn start(raw: bool) -> io::Result<TerminalSession> {
+        let mut session = SESSION.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if session.is_some() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "a terminal session is already active"));
+        }
+
+        let state = enter(raw)?;
+
+        let mut stdout = io::stdout();
+        if let Err(error) = stdout.write_all(ENTER_SEQUENCE.as_bytes()).and_then(|_| stdout.flush()) {
+            leave(state);
+            return Err(error);
+        }
+
+        *session = Some(state);
+        install_panic_hook();
+
+        return Ok(TerminalSession { _not_send: PhantomData });
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+// Put the terminal back the way it was if a session is active
+fn restore() {
+    let state = SESSION.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+
+    if let Some(state) = state {
+        // There is nothing left to do if the terminal is gone
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(LEAVE_SEQUENCE.as_bytes());
+        let _ = stdout.flush();
+
+        leave(state);
+    }
+}
+
+// Restore the terminal before the panic message is printed
+fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+
+    INSTALLED.call_once(|| {
+        let previous = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            restore();
+            previous(info);
+        }));
+    });
+}
+
+#[cfg(unix)]
+fn enter(raw: bool) -> io::Result<SavedState> {
+    if !raw {
+        return Ok(SavedState { termios: None });
+    }
+
+    unsafe {
+        let mut original: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut termios = original;
+        libc::cfmakeraw(&mut termios);
+        if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &termios) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        return Ok(SavedState { termios: Some(original) });
+    }
+}
+
+#[cfg(unix)]
+fn leave(state: SavedState) {
+    if let Some(termios) = state.termios {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &termios);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn enter(raw: bool) -> io::Result<SavedState> {
+    use ::windows::Win32::System::Console;
+
+    let mut state = SavedState { output_mode: None, input_mode: None };
+
+    unsafe {
+        // The escape sequences need virtual terminal processing
+        let output = Console::GetStdHandle(Console::STD_OUTPUT_HANDLE)?;
+        let mut mode = Console::CONSOLE_MODE::default();
+        if Console::GetConsoleMode(output, &mut mode).as_bool()
+            && Console::SetConsoleMode(output, mode | Console::ENABLE_VIRTUAL_TERMINAL_PROCESSING).as_bool()
+        {
+            state.output_mode = Some(mode);
+        }
+
+        if raw {
+            let input = Console::GetStdHandle(Console::STD_INPUT_HANDLE)?;
+            let mut mode = Console::CONSOLE_MODE::default();
+            let cooked = Console::ENABLE_LINE_INPUT | Console::ENABLE_ECHO_INPUT | Console::ENABLE_PROCESSED_INPUT;
+
+            if !Console::GetConsoleMode(input, &mut mode).as_bool() || !Console::SetConsoleMode(input, mode & !cooked).as_bool() {
+                let error = io::Error::last_os_error();
+                leave(state);
+                return Err(error);
+            }
+            state.input_mode = Some(mode);
+        }
+    }
+
+    return Ok(state);
+}
+
+#[cfg(windows)]
+fn leave(state: SavedState) {
+    use ::windows::Win32::System::Console;
+
+    unsafe {
+        if let (Some(mode), Ok(output)) = (state.output_mode, Console::GetStdHandle(Console::STD_OUTPUT_HANDLE)) {
+            Console::SetConsoleMode(output, mode);
+        }
+        if let (Some(mode), Ok(input)) = (state.input_mode, Console::GetStdHandle(Console::STD_INPUT_HANDLE)) {
+            Console::SetConsoleMode(input, mode);
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn enter(raw: bool) -> io::Result<SavedState> {
+    if raw {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "raw mode is not supported on this platform"));
+    }
+
+    return Ok(SavedState {});
+}
+
+#[cfg(not(any(unix, windows)))]
+fn leave(_: SavedState) {}
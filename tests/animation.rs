@@ -0,0 +1,75 @@
+#![allow(clippy::needless_return)]
+
+use std::time::Duration;
+
+use bsgraphlib::{Animation, AnimationMode, BsCmdGraph, Sprite, SpriteSheet};
+
+const TICK: Duration = Duration::from_millis(100);
+
+// Frame shown after every tick, starting with the one shown at the start
+fn frames(mut animation: Animation, ticks: usize) -> Vec<usize> {
+    let mut shown = vec![animation.current_frame().unwrap()];
+    for _ in 0..ticks {
+        animation.update(TICK);
+        shown.push(animation.current_frame().unwrap());
+    }
+
+    return shown;
+}
+
+#[test]
+fn sheets_are_sliced_left_to_right_then_top_to_bottom() {
+    let image = Sprite::from_text("ab.cd\nef.gh\nijklm", '.');
+    let sheet = SpriteSheet::from_grid(&image, 2, 2);
+
+    // The last column and row don't hold a whole frame
+    assert_eq!(sheet.len(), 2);
+    assert_eq!(sheet.frame(0), Some(&Sprite::from_text("ab\nef", '.')));
+    assert_eq!(sheet.frame(1), Some(&Sprite::from_text(".c\n.g", '.')));
+    assert_eq!(sheet.frame(2), None);
+
+    assert!(SpriteSheet::from_grid(&image, 0, 2).is_empty());
+}
+
+#[test]
+fn animations_loop_ping_pong_or_play_once() {
+    let looped = Animation::new(&[0, 1, 2], TICK, AnimationMode::Loop);
+    assert_eq!(frames(looped, 7), vec![0, 1, 2, 0, 1, 2, 0, 1]);
+
+    let ping_pong = Animation::new(&[0, 1, 2], TICK, AnimationMode::PingPong);
+    assert_eq!(frames(ping_pong, 7), vec![0, 1, 2, 1, 0, 1, 2, 1]);
+
+    let mut once = Animation::new(&[0, 1, 2], TICK, AnimationMode::Once);
+    assert_eq!(frames(once.clone(), 5), vec![0, 1, 2, 2, 2, 2]);
+
+    once.update(TICK * 2);
+    assert!(!once.is_finished());
+    once.update(TICK);
+    assert!(once.is_finished());
+    once.reset();
+    assert_eq!(once.current_frame(), Some(0));
+}
+
+#[test]
+fn frames_can_have_their_own_durations() {
+    let animation = Animation::with_durations(vec![(3, TICK), (5, TICK * 3)], AnimationMode::Loop);
+    assert_eq!(frames(animation, 5), vec![3, 5, 5, 5, 3, 5]);
+
+    assert_eq!(Animation::new(&[], TICK, AnimationMode::Loop).current_frame(), None);
+    assert_eq!(Animation::new(&[4, 2], Duration::ZERO, AnimationMode::Loop).current_frame(), Some(4));
+}
+
+#[test]
+fn the_current_frame_is_drawn() {
+    let sheet = SpriteSheet::from_frames(vec![Sprite::from_text("|", ' '), Sprite::from_text("/", ' ')]);
+    let mut spinner = Animation::new(&[0, 1], TICK, AnimationMode::Loop);
+    let mut screen = BsCmdGraph::headless(3, 1, ' ' as i32);
+
+    assert!(screen.draw_animation(&sheet, &spinner, 0, 0));
+    spinner.update(TICK);
+    assert!(screen.draw_animation(&sheet, &spinner, 1, 0));
+    assert!(!screen.draw_animation(&sheet, &spinner, 3, 0));
+    screen.cmd_draw();
+
+    assert_eq!(screen.backend().lines(), vec!["|/ "]);
+}
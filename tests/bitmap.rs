@@ -0,0 +1,100 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BitmapFont, BsGraphError};
+
+// Turn rendered pixels into lines of '#' and '.'
+fn picture(pixels: &[Vec<bool>]) -> Vec<String> {
+    return pixels.iter().map(|row| row.iter().map(|set| if *set { '#' } else { '.' }).collect()).collect();
+}
+
+#[test]
+fn bdf_glyphs_sit_on_the_baseline() {
+    let source = "STARTFONT 2.1
+FONTBOUNDINGBOX 3 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR i
+ENCODING 105
+DWIDTH 2 0
+BBX 1 3 0 0
+BITMAP
+80
+00
+80
+ENDCHAR
+STARTCHAR j
+ENCODING 106
+DWIDTH 3 0
+BBX 2 3 0 -1
+BITMAP
+40
+40
+80
+ENDCHAR
+ENDFONT
+";
+    let font = BitmapFont::parse_bdf(source).unwrap();
+
+    assert_eq!(font.height(), 4);
+    assert_eq!(picture(&font.render("ij")), vec!["#....", "...#.", "#..#.", "..#.."]);
+    assert!(font.glyph('k').is_none());
+}
+
+#[test]
+fn psf_glyphs_are_mapped_by_the_unicode_table() {
+    // PSF2 with two glyphs of 3 by 2 pixels, and a Unicode table
+    let mut psf2 = vec![0x72, 0xb5, 0x4a, 0x86];
+    for field in [0u32, 32, 1, 2, 2, 2, 3] {
+        psf2.extend(field.to_le_bytes());
+    }
+    psf2.extend([0b1010_0000, 0b0100_0000, 0b1110_0000, 0b1110_0000]);
+    psf2.extend("xX".as_bytes());
+    psf2.push(0xFF);
+    psf2.extend("█".as_bytes());
+    psf2.extend([0xFE, b'a', 0xFF]);
+
+    let font = BitmapFont::parse_psf(&psf2).unwrap();
+    assert_eq!(picture(&font.render("x█")), vec!["#.####", ".#.###"]);
+    assert_eq!(font.glyph('X'), font.glyph('x'));
+    assert!(font.glyph('a').is_none());
+
+    // PSF1 without a table maps glyphs by their position
+    let mut psf1 = vec![0x36, 0x04, 0x00, 1];
+    psf1.extend((0..=255u8).map(|i| if i == b'A' { 0b1000_0001 } else { 0 }));
+
+    let font = BitmapFont::parse_psf(&psf1).unwrap();
+    assert_eq!(picture(&font.render("A")), vec!["#......#"]);
+}
+
+#[test]
+fn broken_bitmap_fonts_are_rejected() {
+    assert!(matches!(BitmapFont::parse_bdf("STARTFONT 2.1\nENDFONT\n"), Err(BsGraphError::InvalidFont(_))));
+    assert!(matches!(BitmapFont::parse_psf(&[0x36, 0x04, 0x00, 8, 0xFF]), Err(BsGraphError::InvalidFont(_))));
+
+    // Bitmap rows must be whole bytes of hexadecimal digits
+    let font = |row: &str| format!("STARTFONT 2.1\nFONTBOUNDINGBOX 1 1 0 0\nCHARS 1\nSTARTCHAR a\nENCODING 97\nBBX 1 1 0 0\nBITMAP\n{row}\nENDCHAR\nENDFONT\n");
+    assert!(BitmapFont::parse_bdf(&font("80")).is_ok());
+    for row in ["\u{e9}0", "8", "G0"] {
+        assert!(matches!(BitmapFont::parse_bdf(&font(row)), Err(BsGraphError::InvalidFont(_))));
+    }
+
+    // Sizes and offsets far beyond any real font
+    let font = |header: &str, bbx: &str| format!("STARTFONT 2.1\nFONTBOUNDINGBOX 1 1 0 0\n{header}\nCHARS 1\nSTARTCHAR a\nENCODING 97\n{bbx}\nBITMAP\n80\nENDCHAR\nENDFONT\n");
+    for (header, bbx) in [("FONT_ASCENT 2147483647", "BBX 1 1 0 0"), ("", "BBX 2147483647 1 5 0"), ("", "DWIDTH 100000 0"), ("FONT_DESCENT -2147483648", "")] {
+        assert!(matches!(BitmapFont::parse_bdf(&font(header, bbx)), Err(BsGraphError::InvalidFont(_))));
+    }
+
+    let mut psf = vec![0x72, 0xb5, 0x4a, 0x86];
+    for field in [0, 32, 0, u32::MAX, u32::MAX, 8, 8] {
+        psf.extend(u32::to_le_bytes(field));
+    }
+    assert!(matches!(BitmapFont::parse_psf(&psf), Err(BsGraphError::InvalidFont(_))));
+
+    let font = BitmapFont::builtin();
+    assert_eq!(font.height(), 7);
+    // Missing characters are drawn as a question mark
+    assert_eq!(font.glyph('\u{e9}'), font.glyph('?'));
+}
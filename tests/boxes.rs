@@ -0,0 +1,66 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BorderStyle, BsCmdGraph, BsGraphError, Cell, Color, Rect, Style};
+
+#[test]
+fn rectangles_are_outlined_and_filled() {
+    let mut screen = BsCmdGraph::headless(6, 4, ' ' as i32);
+    assert!(screen.fill_rect(Rect::new(1, 1, 3, 2), Cell::new('.')));
+    assert!(screen.draw_rect(Rect::new(0, 0, 5, 4), Cell::new('#')));
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["##### ", "#...# ", "#...# ", "##### "]);
+
+    // Thin rectangles are just lines, and empty ones draw nothing
+    let mut screen = BsCmdGraph::headless(4, 3, ' ' as i32);
+    assert!(screen.draw_rect(Rect::new(0, 0, 4, 1), Cell::new('-')));
+    assert!(screen.draw_rect(Rect::new(3, 1, 1, 2), Cell::new('|')));
+    assert!(screen.draw_rect(Rect::new(0, 1, 0, 2), Cell::new('#')));
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["----", "   |", "   |"]);
+}
+
+#[test]
+fn boxes_use_the_corners_of_their_border_style() {
+    let styles = [
+        (BorderStyle::Ascii, vec!["+--+", "|  |", "+--+"]),
+        (BorderStyle::Single, vec!["┌──┐", "│  │", "└──┘"]),
+        (BorderStyle::Double, vec!["╔══╗", "║  ║", "╚══╝"]),
+        (BorderStyle::Heavy, vec!["┏━━┓", "┃  ┃", "┗━━┛"]),
+        (BorderStyle::Rounded, vec!["╭──╮", "│  │", "╰──╯"]),
+    ];
+
+    for (border, expected) in styles {
+        let mut screen = BsCmdGraph::headless(4, 3, ' ' as i32);
+        assert!(screen.draw_box(Rect::new(0, 0, 4, 3), border, Style::default()));
+        screen.cmd_draw();
+        assert_eq!(screen.backend().lines(), expected);
+    }
+}
+
+#[test]
+fn titles_are_written_over_the_top_border() {
+    let mut screen = BsCmdGraph::headless(12, 3, ' ' as i32);
+    let style = Style::new().with_fg(Color::Cyan);
+    assert!(screen.draw_titled_box(Rect::new(0, 0, 12, 3), BorderStyle::Rounded, "Title", style));
+    assert_eq!(screen.get_pixel(2, 0), Some(Cell::new('T').with_style(style)));
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["╭ Title ───╮", "│          │", "╰──────────╯"]);
+
+    // Titles that don't fit are cut, and never halfway through a wide character
+    let mut screen = BsCmdGraph::headless(8, 2, ' ' as i32);
+    assert!(screen.draw_titled_box(Rect::new(0, 0, 8, 2), BorderStyle::Single, "Long title", Style::default()));
+    assert!(screen.draw_titled_box(Rect::new(0, 1, 7, 1), BorderStyle::Single, "日本語", Style::default()));
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["┌ Long ┐", "─ 日 ──┘"]);
+}
+
+#[test]
+fn boxes_are_clipped() {
+    let mut screen = BsCmdGraph::headless(3, 3, ' ' as i32);
+    let result = screen.try_draw_box(Rect::new(1, 1, 3, 3), BorderStyle::Single, Style::default());
+    assert!(matches!(result, Err(BsGraphError::OutOfBounds { x: 3, y: 1 })));
+    assert!(!screen.fill_rect(Rect::new(-1, 0, 2, 1), Cell::new('#')));
+
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["#  ", " ┌─", " │ "]);
+}
@@ -0,0 +1,60 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BrailleCanvas, BsCmdGraph, BsGraphError, Cell, Color};
+
+// Rows of the canvas as braille characters, with '.' for empty cells
+fn rows(canvas: &BrailleCanvas) -> Vec<String> {
+    return (0..canvas.rows())
+        .map(|row| (0..canvas.columns()).map(|column| canvas.cell(column, row).map_or('.', |(glyph, _)| glyph)).collect())
+        .collect();
+}
+
+#[test]
+fn every_cell_holds_two_by_four_dots() {
+    let mut canvas = BrailleCanvas::new(2, 1);
+    assert_eq!((canvas.width(), canvas.height()), (4, 4));
+
+    // The dots of the left column, then the bottom right one
+    for y in 0..4 {
+        assert!(canvas.put_pixel(0, y, Color::Red));
+    }
+    canvas.put_pixel(1, 3, Color::Green);
+    assert_eq!(rows(&canvas), vec!["⣇."]);
+    assert_eq!(canvas.cell(0, 0), Some(('⣇', Color::Green)));
+
+    assert!(canvas.get_pixel(1, 3));
+    assert!(canvas.unset_pixel(1, 3));
+    assert!(!canvas.get_pixel(1, 3));
+    assert_eq!(rows(&canvas), vec!["⡇."]);
+
+    assert!(matches!(canvas.try_put_pixel(4, 0, Color::Red), Err(BsGraphError::OutOfBounds { x: 4, y: 0 })));
+    canvas.clear();
+    assert_eq!(rows(&canvas), vec![".."]);
+}
+
+#[test]
+fn lines_and_polygons_are_drawn_in_dots() {
+    let mut canvas = BrailleCanvas::new(2, 1);
+    canvas.draw_line(0, 0, 3, 3, Color::Default);
+    assert_eq!(rows(&canvas), vec!["⠑⢄"]);
+
+    let mut canvas = BrailleCanvas::new(2, 2);
+    assert!(canvas.draw_poly(vec![[0, 0], [3, 0], [3, 7], [0, 7], [0, 0]], Color::Default));
+    assert_eq!(rows(&canvas), vec!["⡏⢹", "⣇⣸"]);
+
+    assert!(!canvas.draw_line(0, 0, 8, 0, Color::Default));
+}
+
+#[test]
+fn canvases_keep_the_background_of_the_screen() {
+    let mut screen = BsCmdGraph::headless(3, 1, ' ' as i32);
+    screen.put_pixel(1, 0, Cell::new(' ').with_bg(Color::Blue));
+
+    let mut canvas = BrailleCanvas::new(2, 1);
+    canvas.draw_line(0, 0, 3, 0, Color::Red);
+
+    assert!(screen.draw_braille(&canvas, 0, 0));
+    assert_eq!(screen.get_pixel(0, 0), Some(Cell::new('⠉').with_fg(Color::Red)));
+    assert_eq!(screen.get_pixel(1, 0), Some(Cell::new('⠉').with_fg(Color::Red).with_bg(Color::Blue)));
+    assert!(!screen.draw_braille(&canvas, 2, 0));
+}
@@ -0,0 +1,34 @@
+use bsgraphlib::{Backend, Cell, Color, ColorDepth, WriterBackend};
+
+#[test]
+fn colors_are_downgraded_to_the_depth() {
+    let orange = Color::Rgb(255, 135, 0);
+
+    assert_eq!(orange.downgrade(ColorDepth::TrueColor), orange);
+    assert_eq!(orange.downgrade(ColorDepth::Ansi256), Color::Indexed(208));
+    assert_eq!(orange.downgrade(ColorDepth::Ansi16), Color::Yellow);
+    assert_eq!(orange.downgrade(ColorDepth::Monochrome), Color::Default);
+
+    assert_eq!(Color::Rgb(128, 128, 128).downgrade(ColorDepth::Ansi256), Color::Indexed(244));
+    assert_eq!(Color::Indexed(1).downgrade(ColorDepth::Ansi16), Color::Red);
+}
+
+#[test]
+fn depth_is_detected_from_the_environment() {
+    assert_eq!(ColorDepth::from_env(Some("truecolor"), Some("xterm")), ColorDepth::TrueColor);
+    assert_eq!(ColorDepth::from_env(None, Some("xterm-256color")), ColorDepth::Ansi256);
+    assert_eq!(ColorDepth::from_env(None, Some("xterm")), ColorDepth::Ansi16);
+    assert_eq!(ColorDepth::from_env(None, Some("dumb")), ColorDepth::Monochrome);
+}
+
+#[test]
+fn writer_backend_emits_colour_sequences() {
+    let mut backend = WriterBackend::new(Vec::new(), 4, 1).with_color_depth(ColorDepth::Ansi256);
+    let cell = Cell::new('#').with_fg(Color::Rgb(255, 135, 0)).with_bg(Color::Blue);
+
+    backend.draw(&[(0, 0, &cell), (1, 0, &cell)]).unwrap();
+    backend.flush().unwrap();
+
+    let output = String::from_utf8(backend.into_inner()).unwrap();
+    assert_eq!(output, "\x1b[1;1H\x1b[0;38;5;208;44m##\x1b[0m");
+}
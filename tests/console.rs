@@ -0,0 +1,38 @@
+use bsgraphlib::console::{self, ConsoleRun};
+use bsgraphlib::{Cell, Color};
+
+// White on black, what a console usually starts with
+const DEFAULT: u16 = 0x07;
+
+#[test]
+fn colours_map_to_console_bits() {
+    assert_eq!(console::color_bits(Color::Red), Some(console::FOREGROUND_RED));
+    assert_eq!(console::color_bits(Color::Cyan), Some(console::FOREGROUND_GREEN | console::FOREGROUND_BLUE));
+    assert_eq!(console::color_bits(Color::BrightBlack), Some(console::FOREGROUND_INTENSITY));
+    assert_eq!(console::color_bits(Color::Default), None);
+
+    let cell = Cell::new('#').with_fg(Color::Yellow).with_bg(Color::Blue);
+    assert_eq!(console::cell_attributes(&cell, DEFAULT), 0x16);
+
+    // Reverse swaps the colours after bold brightened the foreground
+    let cell = Cell::new('#').with_fg(Color::Green).bold().reverse();
+    assert_eq!(console::cell_attributes(&cell, DEFAULT), 0xA0);
+}
+
+#[test]
+fn consecutive_cells_share_a_run() {
+    let a = Cell::new('a');
+    let b = Cell::new('b').with_fg(Color::Red);
+    let emoji = Cell::new('😀');
+
+    let runs = console::console_runs(&[(1, 0, &a), (2, 0, &b), (5, 0, &a), (0, 1, &emoji)], DEFAULT);
+
+    assert_eq!(
+        runs,
+        vec![
+            ConsoleRun { x: 1, y: 0, chars: vec![('a' as u16, 0x07), ('b' as u16, 0x04)] },
+            ConsoleRun { x: 5, y: 0, chars: vec![('a' as u16, 0x07)] },
+            ConsoleRun { x: 0, y: 1, chars: vec![(console::REPLACEMENT, 0x07)] },
+        ]
+    );
+}
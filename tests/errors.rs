@@ -0,0 +1,47 @@
+use bsgraphlib::{BsCmdGraph, BsGraphError, Cell, HeadlessBackend};
+
+#[test]
+fn clipped_drawing_reports_out_of_bounds() {
+    let mut screen = BsCmdGraph::headless(4, 4, '#' as i32);
+
+    assert!(matches!(
+        screen.try_put_pixel(4, 1, Cell::new('#')),
+        Err(BsGraphError::OutOfBounds { x: 4, y: 1 })
+    ));
+
+    // The visible part of a clipped line is still drawn
+    assert!(!screen.draw_line(2, 1, 6, 1, Cell::new('#')));
+    assert_eq!(screen.get_pixel(3, 1), Some(Cell::new('#')));
+
+    assert!(screen.draw_line(0, 0, 3, 0, Cell::new('#')));
+    assert_eq!(screen.get_pixel(3, 0), Some(Cell::new('#')));
+
+    // Lines of a single point still draw it
+    assert!(screen.draw_line(1, 3, 1, 3, Cell::new('@')));
+    assert_eq!(screen.get_pixel(1, 3), Some(Cell::new('@')));
+    assert!(!screen.draw_line(50, 50, 50, 50, Cell::new('@')));
+
+    // So is the visible part of clipped text
+    assert!(matches!(
+        screen.try_draw_text(-1, 2, "abcdef"),
+        Err(BsGraphError::OutOfBounds { x: -1, y: 2 })
+    ));
+    assert_eq!(screen.get_pixel(3, 2), Some(Cell::new('e')));
+}
+
+#[test]
+fn invalid_texture_is_rejected() {
+    let result = BsCmdGraph::try_with_backend(4, 4, 0xD800, HeadlessBackend::new(4, 4));
+
+    assert!(matches!(result, Err(BsGraphError::InvalidGlyph(0xD800))));
+}
+
+#[test]
+fn impossible_sizes_are_rejected() {
+    let result = BsCmdGraph::try_with_backend(50000, 50000, '#' as i32, HeadlessBackend::new(1, 1));
+    assert!(matches!(result, Err(BsGraphError::InvalidSize { width: 50000, height: 50000 })));
+
+    // Negative sizes are just empty
+    let screen = BsCmdGraph::try_with_backend(-3, 50000, '#' as i32, HeadlessBackend::new(1, 1)).unwrap();
+    assert_eq!((screen.width(), screen.height()), (0, 50000));
+}
@@ -0,0 +1,83 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BitmapFont, BsCmdGraph, BsGraphError, Cell, Color, ExportOptions, Image};
+
+const RED: (u8, u8, u8, u8) = (205, 0, 0, 255);
+const BLUE: (u8, u8, u8, u8) = (0, 0, 238, 255);
+
+// A file in the temporary directory, unique to this test run
+fn temporary(name: &str) -> std::path::PathBuf {
+    return std::env::temp_dir().join(format!("bsgraphlib-{}-{}", std::process::id(), name));
+}
+
+#[test]
+fn glyphs_are_drawn_with_the_font() {
+    let mut screen = BsCmdGraph::headless(2, 1, ' ' as i32);
+    screen.put_pixel(0, 0, Cell::new('A').with_fg(Color::Red).with_bg(Color::Blue));
+
+    // The cells are as big as the glyphs, so they aren't scaled
+    let options = ExportOptions::new().with_cell_size(6, 7).with_colors((255, 255, 255), (1, 2, 3));
+    let image = screen.to_image(&options).unwrap();
+    assert_eq!((image.width(), image.height()), (12, 7));
+
+    let glyph = BitmapFont::builtin().glyph('A').unwrap().clone();
+    for y in 0..7 {
+        for x in 0..6 {
+            let expected = if glyph.pixel(x as usize, y as usize) { RED } else { BLUE };
+            assert_eq!(image.get(x, y), Some(expected));
+        }
+    }
+
+    // The default colours come from the options
+    assert_eq!(image.get(9, 3), Some((1, 2, 3, 255)));
+}
+
+#[test]
+fn attributes_and_blocks_are_drawn() {
+    let mut screen = BsCmdGraph::headless(3, 1, ' ' as i32);
+    screen.put_pixel(0, 0, Cell::new(' ').with_fg(Color::Red).with_bg(Color::Blue).reverse());
+    screen.put_pixel(1, 0, Cell::new(' ').with_fg(Color::Red).with_bg(Color::Blue).underline());
+    screen.put_pixel(2, 0, Cell::new('▀').with_fg(Color::Red).with_bg(Color::Blue));
+
+    let image = screen.to_image(&ExportOptions::new().with_cell_size(4, 8)).unwrap();
+
+    assert_eq!(image.get(1, 1), Some(RED));
+    assert_eq!(image.get(5, 6), Some(BLUE));
+    assert_eq!(image.get(5, 7), Some(RED));
+    assert_eq!(image.get(9, 3), Some(RED));
+    assert_eq!(image.get(9, 4), Some(BLUE));
+}
+
+#[test]
+fn images_are_saved_and_loaded_back() {
+    let mut screen = BsCmdGraph::headless(4, 2, ' ' as i32);
+    screen.draw_text(0, 0, "Hi!");
+    let options = ExportOptions::new().with_cell_size(8, 12);
+    let image = screen.to_image(&options).unwrap();
+
+    assert_eq!(Image::parse_pnm(&image.to_ppm()).unwrap(), image);
+
+    let path = temporary("export.ppm");
+    screen.save_image(&path, &options).unwrap();
+    assert_eq!(Image::load(&path).unwrap(), image);
+    std::fs::remove_file(&path).unwrap();
+
+    let path = temporary("export.png");
+    let saved = screen.save_image(&path, &options);
+    if cfg!(feature = "png") {
+        assert!(saved.is_ok());
+        assert_eq!(Image::load(&path).unwrap(), image);
+        std::fs::remove_file(&path).unwrap();
+    } else {
+        assert!(matches!(saved, Err(BsGraphError::InvalidImage(_))));
+    }
+}
+
+#[test]
+fn huge_cells_are_rejected() {
+    let screen = BsCmdGraph::headless(10, 10, ' ' as i32);
+    let options = ExportOptions::new().with_cell_size(100000, 100000);
+
+    assert!(matches!(screen.to_image(&options), Err(BsGraphError::InvalidSize { width: 100000, height: 100000 })));
+    assert!(matches!(screen.save_image(temporary("huge.ppm"), &options), Err(BsGraphError::InvalidSize { .. })));
+}
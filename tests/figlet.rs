@@ -0,0 +1,68 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BsGraphError, FigFont};
+
+// Build a font one row tall, where every character is drawn as itself unless given
+fn font(full_layout: i32, glyphs: &[(char, &str)]) -> FigFont {
+    let mut source = format!("flf2a$ 1 1 4 0 1 0 {}\ntest font\n", full_layout);
+
+    for character in (' '..='~').chain(['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß']) {
+        let glyph = match glyphs.iter().find(|(c, _)| *c == character) {
+            Some((_, glyph)) => glyph.to_string(),
+            None => format!("{}$", character),
+        };
+        source += &format!("{}@@\n", glyph);
+    }
+    source += "0x2192  RIGHTWARDS ARROW\n->@@\n";
+
+    return FigFont::parse(&source).unwrap();
+}
+
+#[test]
+fn layouts_move_characters_together() {
+    let glyphs = [('k', "k "), ('x', " x")];
+
+    assert_eq!(font(0, &glyphs).render("kx"), vec!["k  x"]);
+    assert_eq!(font(64, &glyphs).render("kx"), vec!["kx"]);
+    assert_eq!(font(128 + 1, &glyphs).render("kx"), vec!["kx"]);
+}
+
+#[test]
+fn smushing_rules_merge_characters() {
+    let glyphs = [('L', "<|"), ('R', "|>"), ('/', " /"), ('\\', "\\ "), ('_', "__"), ('[', " ["), (']', "] ")];
+
+    // Equal characters
+    assert_eq!(font(128 + 1, &glyphs).render("LR"), vec!["<|>"]);
+    // Underscores give way to borders
+    assert_eq!(font(128 + 2, &glyphs).render("_R"), vec!["_|>"]);
+    // The later class of the hierarchy wins
+    assert_eq!(font(128 + 4, &glyphs).render("L/"), vec!["</"]);
+    // Opposite brackets become a bar
+    assert_eq!(font(128 + 8, &glyphs).render("[]"), vec![" | "]);
+    // Slashes make a big X
+    assert_eq!(font(128 + 16, &glyphs).render("/\\"), vec![" | "]);
+    // Without a matching rule the characters only touch
+    assert_eq!(font(128 + 16, &glyphs).render("LR"), vec!["<||>"]);
+    // Universal smushing lets the right character win
+    assert_eq!(font(128, &glyphs).render("LR"), vec!["<|>"]);
+}
+
+#[test]
+fn code_tagged_and_missing_characters() {
+    let font = font(64, &[]);
+
+    assert!(font.has_glyph('→'));
+    assert_eq!(font.render("a→b"), vec!["a ->b "]);
+    // Characters that aren't in the font are left out
+    assert_eq!(font.render("a\u{e9}b"), vec!["a b "]);
+}
+
+#[test]
+fn broken_fonts_are_rejected() {
+    assert!(matches!(FigFont::parse("flf2b$ 1 1 4 0 0"), Err(BsGraphError::InvalidFont(_))));
+    assert!(matches!(FigFont::parse("flf2a$ 1 1 4 0 0\n @@\n!@@\n"), Err(BsGraphError::InvalidFont(_))));
+    assert!(matches!(FigFont::load("no/such/font.flf"), Err(BsGraphError::Io(_))));
+
+    assert_eq!(FigFont::block().height(), 7);
+    assert_eq!(FigFont::small().height(), 3);
+}
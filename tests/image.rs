@@ -0,0 +1,186 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BsGraphError, Cell, Color, ColorDepth, Dither, Image, ImageMode, ImageOptions, Sprite};
+
+// Rows of a sprite, with '.' for transparent cells
+fn rows(sprite: &Sprite) -> Vec<String> {
+    return (0..sprite.height())
+        .map(|y| (0..sprite.width()).map(|x| sprite.get(x, y).map_or(".".to_owned(), |cell| cell.glyph.clone())).collect())
+        .collect();
+}
+
+// An opaque gray image
+fn gray(width: i32, height: i32, levels: &[u8]) -> Image {
+    return Image::from_rgb(width, height, levels.iter().map(|level| (*level, *level, *level)).collect()).unwrap();
+}
+
+/* A BMP file with a version 1 header
+ * Rows are given from the top and padded to 4 bytes here
+*/
+fn bmp(width: i32, height: i32, bits: u16, compression: u32, palette: &[[u8; 4]], rows: &[Vec<u8>]) -> Vec<u8> {
+    let data_offset = 14 + 40 + palette.len() * 4;
+    let mut bytes = b"BM".to_vec();
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend((data_offset as u32).to_le_bytes());
+
+    bytes.extend(40u32.to_le_bytes());
+    bytes.extend(width.to_le_bytes());
+    bytes.extend(height.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(bits.to_le_bytes());
+    bytes.extend(compression.to_le_bytes());
+    bytes.extend([0; 12]);
+    bytes.extend((palette.len() as u32).to_le_bytes());
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend(palette.iter().flatten());
+
+    // Stored from the bottom up
+    for row in rows.iter().rev() {
+        let mut row = row.clone();
+        row.resize(row.len().div_ceil(4) * 4, 0);
+        bytes.extend(row);
+    }
+
+    return bytes;
+}
+
+#[test]
+fn pnm_images_are_parsed() {
+    let plain = Image::parse_pnm(b"P3\n# a comment\n2 1 15\n15 0 0  0 15 15\n").unwrap();
+    assert_eq!((plain.width(), plain.height()), (2, 1));
+    assert_eq!(plain.get(0, 0), Some((255, 0, 0, 255)));
+    assert_eq!(plain.get(1, 0), Some((0, 255, 255, 255)));
+    assert_eq!(plain.get(2, 0), None);
+
+    let binary = Image::parse_pnm(b"P5 2 2 255\n\x00\x40\x80\xff").unwrap();
+    assert_eq!(binary.get(1, 0), Some((64, 64, 64, 255)));
+    assert_eq!(binary.get(0, 1), Some((128, 128, 128, 255)));
+
+    let wide = Image::parse_pnm(b"P6 1 1 65535\n\xff\xff\x00\x00\x80\x00").unwrap();
+    assert_eq!(wide.get(0, 0), Some((255, 0, 127, 255)));
+
+    assert!(matches!(Image::parse_pnm(b"P5 2 2 255\n\x00"), Err(BsGraphError::InvalidImage(_))));
+    assert!(matches!(Image::parse_pnm(b"P4 1 1\n\x00"), Err(BsGraphError::InvalidImage(_))));
+}
+
+#[test]
+fn bmp_images_are_parsed() {
+    // 24 bits per pixel, stored as blue, green, red
+    let rows = vec![vec![0, 0, 255, 0, 255, 0], vec![255, 0, 0, 255, 255, 255]];
+    let image = Image::parse_bmp(&bmp(2, 2, 24, 0, &[], &rows)).unwrap();
+    assert_eq!(image.get(0, 0), Some((255, 0, 0, 255)));
+    assert_eq!(image.get(1, 0), Some((0, 255, 0, 255)));
+    assert_eq!(image.get(0, 1), Some((0, 0, 255, 255)));
+    assert_eq!(image.get(1, 1), Some((255, 255, 255, 255)));
+
+    // 4 bits per pixel, two pixels per byte
+    let palette = [[0, 0, 0, 0], [0, 0, 255, 0], [255, 255, 255, 0]];
+    let image = Image::parse_bmp(&bmp(3, 1, 4, 0, &palette, &[vec![0x12, 0x00]])).unwrap();
+    assert_eq!(image.get(0, 0), Some((255, 0, 0, 255)));
+    assert_eq!(image.get(1, 0), Some((255, 255, 255, 255)));
+    assert_eq!(image.get(2, 0), Some((0, 0, 0, 255)));
+
+    // Decoded by their contents
+    assert_eq!(Image::decode(&bmp(2, 2, 24, 0, &[], &rows)).unwrap().get(1, 1), Some((255, 255, 255, 255)));
+
+    // Run length encoding isn't supported
+    assert!(matches!(Image::parse_bmp(&bmp(1, 1, 8, 1, &palette, &[vec![0]])), Err(BsGraphError::InvalidImage(_))));
+}
+
+#[test]
+fn huge_sizes_are_rejected_before_reading_the_pixels() {
+    // Headers claiming far more pixels than the file holds
+    for size in [i32::MAX, 60000] {
+        let truncated = bmp(size, size, 24, 0, &[], &[]);
+        assert!(matches!(Image::parse_bmp(&truncated), Err(BsGraphError::InvalidImage(_))));
+    }
+    assert!(matches!(Image::parse_pnm(b"P6 2147483647 2147483647 65535\n\x00"), Err(BsGraphError::InvalidImage(_))));
+
+    assert!(matches!(Image::from_rgba(65536, 65536, Vec::new()), Err(BsGraphError::SizeMismatch { actual: 0, .. })));
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn png_images_are_parsed() {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255, 0, 0, 255, 0, 0, 255, 0]).unwrap();
+    }
+
+    let image = Image::decode(&bytes).unwrap();
+    assert_eq!(image.get(0, 0), Some((255, 0, 0, 255)));
+    assert_eq!(image.get(1, 0), Some((0, 0, 255, 0)));
+
+    let sprite = image.render(2, 1, &ImageOptions::new().with_mode(ImageMode::HalfBlock));
+    assert_eq!(rows(&sprite), vec!["▀."]);
+}
+
+#[cfg(not(feature = "png"))]
+#[test]
+fn png_images_need_the_png_feature() {
+    let bytes = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    assert!(matches!(Image::decode(&bytes), Err(BsGraphError::InvalidImage(_))));
+}
+
+#[test]
+fn images_are_resized_by_averaging() {
+    let image = gray(4, 2, &[0, 100, 200, 200, 100, 200, 0, 0]);
+    let half = image.resize(2, 1);
+
+    assert_eq!(half.get(0, 0), Some((100, 100, 100, 255)));
+    assert_eq!(half.get(1, 0), Some((100, 100, 100, 255)));
+    assert_eq!(image.resize(8, 4).get(7, 3), Some((0, 0, 0, 255)));
+}
+
+#[test]
+fn brightness_picks_a_character_of_the_ramp() {
+    let ramp: Vec<u8> = (0..10).map(|i| (i * 255 / 9) as u8).collect();
+    let image = gray(10, 1, &ramp);
+
+    assert_eq!(rows(&image.render(10, 1, &ImageOptions::new())), vec![" .:-=+*#%@"]);
+    assert_eq!(rows(&image.render(10, 1, &ImageOptions::new().with_invert(true))), vec!["@%#*+=-:. "]);
+    assert_eq!(rows(&image.render(5, 1, &ImageOptions::new().with_ramp("01"))), vec!["00011"]);
+
+    let colored = Image::from_rgb(1, 1, vec![(255, 0, 0)]).unwrap().render(1, 1, &ImageOptions::new().with_color(true));
+    assert_eq!(colored.get(0, 0), Some(&Cell::new(':').with_fg(Color::Rgb(255, 0, 0))));
+}
+
+#[test]
+fn half_blocks_hold_two_pixels() {
+    let image = Image::from_rgba(2, 2, vec![(255, 0, 0, 255), (0, 0, 0, 0), (0, 0, 255, 255), (0, 255, 0, 255)]).unwrap();
+    let sprite = image.render(2, 1, &ImageOptions::new().with_mode(ImageMode::HalfBlock));
+
+    assert_eq!(sprite.get(0, 0), Some(&Cell::new('▀').with_fg(Color::Rgb(255, 0, 0)).with_bg(Color::Rgb(0, 0, 255))));
+    assert_eq!(sprite.get(1, 0), Some(&Cell::new('▄').with_fg(Color::Rgb(0, 255, 0))));
+
+    // Colours are reduced to the palette
+    let options = ImageOptions::new().with_mode(ImageMode::HalfBlock).with_depth(ColorDepth::Ansi16);
+    assert_eq!(image.render(2, 1, &options).get(0, 0).unwrap().fg, Color::BrightRed);
+}
+
+#[test]
+fn braille_dots_are_lit_by_bright_pixels() {
+    let image = gray(2, 4, &[255, 0, 255, 0, 255, 0, 255, 0]);
+    assert_eq!(rows(&image.render(1, 1, &ImageOptions::new().with_mode(ImageMode::Braille))), vec!["⡇"]);
+
+    let image = gray(4, 4, &[0; 16]);
+    assert_eq!(rows(&image.render(2, 1, &ImageOptions::new().with_mode(ImageMode::Braille))), vec!["\u{2800}\u{2800}"]);
+}
+
+#[test]
+fn dithering_mixes_levels() {
+    let image = gray(4, 4, &[128; 16]);
+    let options = ImageOptions::new().with_ramp(" #");
+    let lit = |sprite: &Sprite| rows(sprite).concat().matches('#').count();
+
+    assert_eq!(lit(&image.render(4, 4, &options)), 16);
+    assert_eq!(
+        rows(&image.render(4, 4, &options.clone().with_dither(Dither::Ordered))),
+        vec!["# # ", " # #", "# # ", " # #"]
+    );
+    assert_eq!(lit(&image.render(4, 4, &options.clone().with_dither(Dither::FloydSteinberg))), 8);
+}
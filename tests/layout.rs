@@ -0,0 +1,37 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{Align, BsCmdGraph, BsGraphError, Rect, TextOptions, VAlign};
+
+#[test]
+fn text_block_reports_its_bounding_box() {
+    let mut screen = BsCmdGraph::headless(20, 6, '#' as i32);
+
+    let options = TextOptions::new().with_align(Align::Center).with_valign(VAlign::Middle);
+    let bounds = screen.draw_text_block(Rect::new(2, 1, 10, 5), "one two\nthree", &options);
+    assert_eq!(bounds, Rect::new(3, 2, 7, 2));
+
+    // Nothing is drawn for empty text, but the box still sits where it would go
+    let bounds = screen.draw_text_block(Rect::new(2, 1, 10, 5), "", &TextOptions::new());
+    assert_eq!(bounds, Rect::new(2, 1, 0, 0));
+}
+
+#[test]
+fn long_words_are_broken() {
+    let mut screen = BsCmdGraph::headless(6, 3, '#' as i32);
+
+    screen.draw_text_block(Rect::new(0, 0, 4, 3), "a wonderful day", &TextOptions::new());
+    screen.cmd_draw();
+
+    assert_eq!(screen.backend().lines(), vec!["a     ", "wond  ", "erfu  "]);
+}
+
+#[test]
+fn clipped_text_block_reports_out_of_bounds() {
+    let mut screen = BsCmdGraph::headless(6, 2, '#' as i32);
+
+    let result = screen.try_draw_text_block(Rect::new(3, 0, 5, 2), "clipped", &TextOptions::new());
+    assert!(matches!(result, Err(BsGraphError::OutOfBounds { x: 6, y: 0 })));
+
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["   cli", "   ed "]);
+}
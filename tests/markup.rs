@@ -0,0 +1,36 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{parse_markup, Color, Span, Style};
+
+#[test]
+fn markup_is_parsed_into_spans() {
+    let spans = parse_markup("[red]error[/] in [bold]main [on blue]here[/][/]!");
+
+    assert_eq!(
+        spans,
+        vec![
+            Span::new("error", Style::new().with_fg(Color::Red)),
+            Span::raw(" in "),
+            Span::new("main ", Style::new().bold()),
+            Span::new("here", Style::new().bold().with_bg(Color::Blue)),
+            Span::raw("!"),
+        ]
+    );
+}
+
+#[test]
+fn colors_are_parsed_by_name() {
+    let spans = parse_markup("[bright_white on #ff8800]a[/][208]b");
+
+    assert_eq!(spans[0].style, Style::new().with_fg(Color::BrightWhite).with_bg(Color::Rgb(255, 136, 0)));
+    assert_eq!(spans[1].style, Style::new().with_fg(Color::Indexed(208)));
+
+    assert_eq!("bright red".parse::<Color>().unwrap(), Color::BrightRed);
+    assert_eq!(Color::Indexed(42).to_string().parse::<Color>().unwrap(), Color::Indexed(42));
+    assert!("reddish".parse::<Color>().is_err());
+}
+
+#[test]
+fn anything_but_a_tag_is_text() {
+    assert_eq!(parse_markup("[[red] [nope] a[x] [/] [unclosed"), vec![Span::raw("[red] [nope] a[x]  [unclosed")]);
+}
@@ -0,0 +1,87 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BsCmdGraph, BsGraphError, Cell, Color, ExportOptions, PixelCanvas, PixelMode};
+
+// Rows of the canvas as block characters, with '.' for empty cells
+fn rows(canvas: &PixelCanvas) -> Vec<String> {
+    return (0..canvas.rows())
+        .map(|row| (0..canvas.columns()).map(|column| canvas.cell(column, row).map_or('.', |(glyph, _, _)| glyph)).collect())
+        .collect();
+}
+
+#[test]
+fn half_blocks_show_two_colours() {
+    let mut canvas = PixelCanvas::new(3, 1, PixelMode::HalfBlock);
+    assert_eq!((canvas.width(), canvas.height()), (3, 2));
+
+    canvas.put_pixel(0, 0, Color::Red);
+    canvas.put_pixel(0, 1, Color::Blue);
+    canvas.put_pixel(1, 1, Color::Green);
+
+    assert_eq!(canvas.cell(0, 0), Some(('▀', Color::Red, Some(Color::Blue))));
+    assert_eq!(canvas.cell(1, 0), Some(('▄', Color::Green, None)));
+    assert_eq!(canvas.cell(2, 0), None);
+
+    assert_eq!(canvas.get_pixel(0, 1), Some(Color::Blue));
+    assert!(canvas.unset_pixel(0, 1));
+    assert_eq!(canvas.cell(0, 0), Some(('▀', Color::Red, None)));
+
+    assert!(matches!(canvas.try_put_pixel(0, 2, Color::Red), Err(BsGraphError::OutOfBounds { x: 0, y: 2 })));
+}
+
+#[test]
+fn quadrants_use_the_two_most_common_colours() {
+    let mut canvas = PixelCanvas::new(2, 1, PixelMode::Quadrant);
+    assert!(canvas.draw_line(0, 0, 1, 1, Color::Red));
+    assert_eq!(rows(&canvas), vec!["▚."]);
+
+    canvas.put_pixel(2, 0, Color::Blue);
+    canvas.put_pixel(3, 0, Color::Green);
+    canvas.put_pixel(3, 1, Color::Green);
+    assert_eq!(canvas.cell(1, 0), Some(('▐', Color::Green, Some(Color::Blue))));
+
+    canvas.clear();
+    assert_eq!(rows(&canvas), vec![".."]);
+}
+
+#[test]
+fn sextants_split_cells_in_six() {
+    let mut canvas = PixelCanvas::new(4, 1, PixelMode::Sextant);
+    assert_eq!((canvas.width(), canvas.height()), (8, 3));
+
+    // Top left, the left column, everything but the top left, and everything
+    canvas.put_pixel(0, 0, Color::Default);
+    assert!(canvas.draw_line(2, 0, 2, 2, Color::Default));
+    assert!(canvas.draw_poly(vec![[5, 0], [5, 2], [4, 2], [4, 1], [5, 1]], Color::Default));
+    for x in 6..8 {
+        assert!(canvas.draw_line(x, 0, x, 2, Color::Default));
+    }
+
+    assert_eq!(rows(&canvas), vec!["\u{1fb00}▌\u{1fb3b}█"]);
+}
+
+#[test]
+fn canvases_are_drawn_and_exported() {
+    let mut screen = BsCmdGraph::headless(2, 1, ' ' as i32);
+    screen.put_pixel(0, 0, Cell::new(' ').with_bg(Color::Blue));
+
+    let mut canvas = PixelCanvas::new(2, 1, PixelMode::Sextant);
+    canvas.put_pixel(1, 1, Color::Red);
+    canvas.put_pixel(0, 2, Color::Red);
+    canvas.put_pixel(2, 0, Color::Red);
+    canvas.put_pixel(3, 0, Color::Green);
+
+    assert!(screen.draw_pixels(&canvas, 0, 0));
+    assert_eq!(screen.get_pixel(0, 0), Some(Cell::new('\u{1fb16}').with_fg(Color::Red).with_bg(Color::Blue)));
+    assert_eq!(screen.get_pixel(1, 0), Some(Cell::new('\u{1fb00}').with_fg(Color::Red).with_bg(Color::Green)));
+    assert!(!screen.draw_pixels(&canvas, 1, 0));
+
+    // Every pixel of the canvas becomes a pixel of the image
+    let image = screen.to_image(&ExportOptions::new().with_cell_size(2, 3)).unwrap();
+    let red = Color::Red.to_rgb().map(|(r, g, b)| (r, g, b, 255));
+    let blue = Color::Blue.to_rgb().map(|(r, g, b)| (r, g, b, 255));
+    assert_eq!(image.get(1, 1), red);
+    assert_eq!(image.get(0, 2), red);
+    assert_eq!(image.get(0, 0), blue);
+    assert_eq!(image.get(1, 2), blue);
+}
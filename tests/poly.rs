@@ -0,0 +1,80 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BsCmdGraph, BsGraphError, Cell, FillRule, HeadlessBackend, PolyOptions};
+
+// Lines of a screen after drawing a polygon on it with the given function
+fn draw<F: FnOnce(&mut BsCmdGraph<HeadlessBackend>)>(width: i32, height: i32, draw: F) -> Vec<String> {
+    let mut screen = BsCmdGraph::headless(width, height, ' ' as i32);
+    draw(&mut screen);
+    screen.cmd_draw();
+    return screen.backend().lines();
+}
+
+#[test]
+fn closed_polygons_join_the_last_vertex_to_the_first() {
+    let triangle = vec![[0, 0], [4, 0], [0, 4]];
+
+    let open = draw(5, 5, |screen| {
+        assert!(screen.draw_poly(triangle.clone(), Cell::new('#')));
+    });
+    assert_eq!(open, vec!["#####", "   # ", "  #  ", " #   ", "#    "]);
+
+    let closed = draw(5, 5, |screen| {
+        assert!(screen.draw_poly_with(triangle.clone(), Cell::new('#'), &PolyOptions::new().with_closed(true)));
+    });
+    assert_eq!(closed, vec!["#####", "#  # ", "# #  ", "##   ", "#    "]);
+}
+
+#[test]
+fn concave_polygons_are_filled() {
+    let lines = draw(9, 5, |screen| {
+        let u = vec![[0, 0], [2, 0], [2, 3], [6, 3], [6, 0], [8, 0], [8, 4], [0, 4]];
+        assert!(screen.fill_poly(u, Cell::new('#'), &PolyOptions::new()));
+    });
+    assert_eq!(lines, vec!["###   ###", "###   ###", "###   ###", "#########", "#########"]);
+}
+
+#[test]
+fn fill_rules_decide_the_inside_of_crossing_polygons() {
+    // A star drawn in one stroke, where the middle is wound around twice
+    let star = vec![[4, 0], [7, 8], [0, 3], [8, 3], [1, 8]];
+
+    let even_odd = draw(9, 9, |screen| {
+        screen.fill_poly(star.clone(), Cell::new('#'), &PolyOptions::new().with_fill_rule(FillRule::EvenOdd));
+    });
+    let non_zero = draw(9, 9, |screen| {
+        screen.fill_poly(star.clone(), Cell::new('#'), &PolyOptions::new().with_fill_rule(FillRule::NonZero));
+    });
+
+    // The even-odd rule leaves a hole in the middle, with only its outline
+    assert_eq!(
+        even_odd,
+        vec!["    #    ", "    #    ", "   ###   ", "#########", " ###  ## ", "  ## ##  ", "  #####  ", " ### ### ", " #     # "]
+    );
+    assert_eq!(
+        non_zero,
+        vec!["    #    ", "    #    ", "   ###   ", "#########", " ####### ", "  #####  ", "  #####  ", " ### ### ", " #     # "]
+    );
+}
+
+#[test]
+fn filled_polygons_are_clipped() {
+    let mut screen = BsCmdGraph::headless(4, 4, ' ' as i32);
+    let result = screen.try_fill_poly(vec![[-1, 0], [2, 0], [2, 2]], Cell::new('#'), &PolyOptions::new());
+    assert!(matches!(result, Err(BsGraphError::OutOfBounds { x: -1, y: 0 })));
+
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["### ", "### ", "  # ", "    "]);
+}
+
+#[test]
+fn huge_polygons_only_fill_what_is_visible() {
+    // Far too many cells to go through one by one, but only a few of them are on screen
+    let mut screen = BsCmdGraph::headless(4, 3, ' ' as i32);
+    let square = vec![[-50000, -50000], [50000, -50000], [50000, 50000], [-50000, 50000]];
+    let result = screen.try_fill_poly(square, Cell::new('#'), &PolyOptions::new());
+    assert!(matches!(result, Err(BsGraphError::OutOfBounds { x: -50000, y: -50000 })));
+
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["####", "####", "####"]);
+}
@@ -0,0 +1,33 @@
+use bsgraphlib::{BsCmdGraph, Cell, Event, ResizePolicy};
+
+#[test]
+fn resize_preserves_or_clears_content() {
+    let mut screen = BsCmdGraph::headless(4, 3, '#' as i32);
+    screen.put_pixel(1, 1, Cell::new('a'));
+    screen.put_pixel(3, 2, Cell::new('b'));
+
+    screen.resize(3, 4);
+    assert_eq!((screen.width(), screen.height()), (3, 4));
+    assert_eq!(screen.get_pixel(1, 1), Some(Cell::new('a')));
+    assert_eq!(screen.get_pixel(3, 2), None);
+
+    screen.set_resize_policy(ResizePolicy::Clear);
+    screen.resize(5, 5);
+    assert_eq!(screen.get_pixel(1, 1), Some(Cell::default()));
+}
+
+#[test]
+fn display_follows_the_backend() {
+    let mut screen = BsCmdGraph::headless(4, 3, '#' as i32);
+    screen.set_follow_size(true);
+    assert_eq!(screen.poll_event(), None);
+
+    // Headless backends are asked for their size without waiting for a signal
+    screen.backend_mut().resize(6, 2);
+    assert_eq!(screen.poll_event(), Some(Event::Resize { width: 6, height: 2 }));
+    assert_eq!(screen.poll_event(), None);
+
+    screen.put_pixel(5, 1, Cell::new('@'));
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["      ", "     @"]);
+}
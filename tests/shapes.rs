@@ -0,0 +1,97 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BsCmdGraph, BsGraphError, Cell};
+
+#[test]
+fn circles_are_squashed_by_the_cell_aspect() {
+    let mut screen = BsCmdGraph::headless(21, 11, ' ' as i32);
+    assert_eq!(screen.cell_aspect(), 2.0);
+
+    assert!(screen.draw_circle(10, 5, 8, Cell::new('#')));
+    screen.cmd_draw();
+    assert_eq!(
+        screen.backend().lines(),
+        vec![
+            "                     ",
+            "       #######       ",
+            "    ###       ###    ",
+            "   #             #   ",
+            "  #               #  ",
+            "  #               #  ",
+            "  #               #  ",
+            "   #             #   ",
+            "    ###       ###    ",
+            "       #######       ",
+            "                     ",
+        ]
+    );
+
+    // Square cells keep circles as tall as they are wide
+    let mut screen = BsCmdGraph::headless(5, 5, ' ' as i32);
+    screen.set_cell_aspect(1.0);
+    screen.fill_circle(2, 2, 2, Cell::new('#'));
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec![" ### ", "#####", "#####", "#####", " ### "]);
+}
+
+#[test]
+fn filled_ellipses_match_their_outline() {
+    let mut screen = BsCmdGraph::headless(21, 11, ' ' as i32);
+
+    screen.draw_ellipse(10, 5, 9, 5, Cell::new('o'));
+    screen.fill_ellipse(10, 5, 7, 3, Cell::new('#'));
+    screen.draw_ellipse(10, 5, 7, 3, Cell::new('+'));
+    screen.cmd_draw();
+
+    assert_eq!(
+        screen.backend().lines(),
+        vec![
+            "       ooooooo       ",
+            "    ooo       ooo    ",
+            "   o   +++++++   o   ",
+            "  o +++#######+++ o  ",
+            " o +#############+ o ",
+            " o +#############+ o ",
+            " o +#############+ o ",
+            "  o +++#######+++ o  ",
+            "   o   +++++++   o   ",
+            "    ooo       ooo    ",
+            "       ooooooo       ",
+        ]
+    );
+}
+
+#[test]
+fn arcs_go_counterclockwise() {
+    let mut screen = BsCmdGraph::headless(21, 11, ' ' as i32);
+
+    screen.draw_arc(10, 5, 8, 0.0, 90.0, Cell::new('a'));
+    screen.draw_arc(10, 5, 8, 180.0, 225.0, Cell::new('b'));
+    screen.cmd_draw();
+
+    assert_eq!(
+        screen.backend().lines(),
+        vec![
+            "                     ",
+            "          aaaa       ",
+            "              aaa    ",
+            "                 a   ",
+            "                  a  ",
+            "  b               a  ",
+            "  b                  ",
+            "   b                 ",
+            "    b                ",
+            "                     ",
+            "                     ",
+        ]
+    );
+}
+
+#[test]
+fn clipped_shapes_report_out_of_bounds() {
+    let mut screen = BsCmdGraph::headless(5, 5, ' ' as i32);
+
+    assert!(matches!(screen.try_draw_circle(0, 2, 2, Cell::new('#')), Err(BsGraphError::OutOfBounds { x: -1, y: 1 })));
+    assert!(!screen.fill_ellipse(4, 4, 1, 1, Cell::new('#')));
+    assert!(screen.draw_arc(2, 2, 2, 0.0, 90.0, Cell::new('#')));
+}
@@ -0,0 +1,237 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{
+    snapshot, Align, BitmapFont, BitmapMode, BitmapOptions, BrailleCanvas, BsCmdGraph, Cell, Color, ColorDepth, FigFont,
+    HeadlessBackend, Image, ImageMode, ImageOptions, PixelCanvas, PixelMode, Rect, Sprite, Style, TextOptions, VAlign,
+};
+
+// Path of a golden file in tests/snapshots
+fn golden(name: &str) -> String {
+    return format!("{}/tests/snapshots/{}.txt", env!("CARGO_MANIFEST_DIR"), name);
+}
+
+// Draw the display and compare it against its golden file
+fn check(mut screen: BsCmdGraph<HeadlessBackend>, name: &str) {
+    screen.cmd_draw();
+    snapshot::assert_snapshot(screen.backend(), golden(name));
+}
+
+#[test]
+fn draw_line() {
+    let mut screen = BsCmdGraph::headless(12, 6, '#' as i32);
+
+    screen.draw_line(0, 0, 11, 5, Cell::new('#'));
+    screen.draw_line(0, 5, 6, 5, Cell::new('=').with_fg(Color::Red));
+
+    check(screen, "draw_line");
+}
+
+#[test]
+fn draw_poly() {
+    let mut screen = BsCmdGraph::headless(12, 8, '#' as i32);
+
+    let triangle = vec![[1, 6], [5, 1], [10, 6], [1, 6]];
+    screen.draw_poly(triangle, Cell::new('*'));
+
+    check(screen, "draw_poly");
+}
+
+#[test]
+fn draw_text() {
+    let mut screen = BsCmdGraph::headless(16, 3, '#' as i32);
+
+    screen.draw_text(1, 0, "Hello, World!");
+    screen.draw_text(10, 2, "clipped text");
+
+    check(screen, "draw_text");
+}
+
+#[test]
+fn draw_styled_text() {
+    let mut screen = BsCmdGraph::headless(14, 3, '#' as i32);
+
+    screen.draw_styled_text(0, 0, "Warning:", Style::new().with_fg(Color::Yellow).bold());
+    screen.draw_text(9, 0, "low");
+
+    let rainbow = [Color::Red, Color::Yellow, Color::Green, Color::Cyan, Color::Blue];
+    screen.draw_text_with(0, 1, "rainbow", |i, _| Style::new().with_fg(rainbow[i % rainbow.len()]));
+
+    // Later drawings go over the text, and text starting off screen is clipped
+    screen.draw_line(3, 1, 3, 2, Cell::new('|'));
+    screen.draw_text(-3, 2, "offset");
+
+    check(screen, "draw_styled_text");
+}
+
+#[test]
+fn draw_text_block() {
+    let mut screen = BsCmdGraph::headless(51, 12, '#' as i32);
+    let text = "The quick brown fox jumps over the lazy dog";
+
+    for (i, align) in [Align::Left, Align::Center, Align::Right, Align::Justify].into_iter().enumerate() {
+        let rect = Rect::new(i as i32 * 13, 0, 12, 5);
+        screen.draw_text_block(rect, text, &TextOptions::new().with_align(align));
+    }
+
+    // Newlines start a new line, and text that doesn't fit ends with the ellipsis
+    let options = TextOptions::new().with_valign(VAlign::Bottom).with_ellipsis("…");
+    screen.draw_text_block(Rect::new(0, 6, 10, 3), "First line\n\nand a much longer one", &options);
+
+    let options = TextOptions::new().with_wrap(false).with_align(Align::Right).with_ellipsis("...");
+    screen.draw_text_block(Rect::new(12, 6, 12, 3), "short\nthis one is too long", &options);
+
+    let options = TextOptions::new().with_align(Align::Center).with_valign(VAlign::Middle);
+    screen.draw_text_block(Rect::new(0, 9, 51, 3), "centred", &options);
+
+    check(screen, "draw_text_block");
+}
+
+#[test]
+fn draw_markup() {
+    let mut screen = BsCmdGraph::headless(20, 4, '#' as i32);
+
+    let text = "[bold red]error[/]: expected [u]one[/] of [green]ok[/] or [on blue]fail[/]";
+    screen.draw_markup(Rect::new(0, 0, 20, 4), text, &TextOptions::new());
+
+    check(screen, "draw_markup");
+}
+
+#[test]
+fn draw_banner() {
+    let mut screen = BsCmdGraph::headless(24, 11, '#' as i32);
+
+    // The letters are transparent, and the banner is clipped at the edge
+    screen.draw_line(0, 1, 23, 1, Cell::new('-'));
+    screen.draw_styled_banner(1, 0, "Hi 42", &FigFont::small(), Style::new().with_fg(Color::Cyan));
+    screen.draw_banner(-2, 4, "GO", &FigFont::block());
+    screen.draw_banner(14, 4, "!", &FigFont::block());
+
+    check(screen, "draw_banner");
+}
+
+#[test]
+fn draw_bitmap_text() {
+    let mut screen = BsCmdGraph::headless(44, 18, '#' as i32);
+    let font = BitmapFont::builtin();
+
+    screen.draw_bitmap_text(0, 0, "12:30", &font, &BitmapOptions::new());
+
+    let options = BitmapOptions::new().with_scale(2).with_mode(BitmapMode::Fill('@')).with_style(Style::new().with_fg(Color::Green));
+    screen.draw_bitmap_text(18, 4, "42", &font, &options);
+
+    check(screen, "draw_bitmap_text");
+}
+
+#[test]
+fn draw_image() {
+    let mut screen = BsCmdGraph::headless(16, 6, '#' as i32);
+
+    // A gray ramp over a coloured stripe, with a transparent corner
+    let mut pixels = Vec::new();
+    for y in 0..4 {
+        for x in 0..8 {
+            pixels.push(match (x, y) {
+                (0, 0) => (0, 0, 0, 0),
+                (_, 3) => (255, 0, 0, 255),
+                _ => (x * 36, x * 36, x * 36, 255),
+            });
+        }
+    }
+    let image = Image::from_rgba(8, 4, pixels).unwrap();
+
+    screen.draw_image(&image, Rect::new(0, 0, 8, 4), &ImageOptions::new());
+    let options = ImageOptions::new().with_mode(ImageMode::HalfBlock).with_depth(ColorDepth::Ansi16);
+    screen.draw_image(&image, Rect::new(9, 1, 8, 2), &options);
+
+    check(screen, "draw_image");
+}
+
+#[test]
+fn draw_braille() {
+    let mut screen = BsCmdGraph::headless(12, 5, '#' as i32);
+    let mut canvas = BrailleCanvas::new(10, 4);
+
+    canvas.draw_poly(vec![[1, 14], [10, 1], [19, 14], [1, 14]], Color::Yellow);
+    canvas.draw_line(0, 0, 19, 15, Color::Cyan);
+    screen.draw_braille(&canvas, 1, 0);
+
+    check(screen, "draw_braille");
+}
+
+#[test]
+fn draw_pixels() {
+    let mut screen = BsCmdGraph::headless(16, 5, '#' as i32);
+
+    let mut half_blocks = PixelCanvas::new(7, 4, PixelMode::HalfBlock);
+    half_blocks.draw_poly(vec![[0, 7], [3, 0], [6, 7], [0, 7]], Color::Green);
+    half_blocks.draw_line(0, 0, 6, 0, Color::Red);
+    screen.draw_pixels(&half_blocks, 0, 0);
+
+    let mut sextants = PixelCanvas::new(7, 4, PixelMode::Sextant);
+    sextants.draw_poly(vec![[1, 1], [12, 1], [12, 10], [1, 10], [1, 1]], Color::Blue);
+    sextants.draw_line(1, 1, 12, 10, Color::Yellow);
+    screen.draw_pixels(&sextants, 8, 0);
+
+    check(screen, "draw_pixels");
+}
+
+#[test]
+fn draw_text_unicode() {
+    let mut screen = BsCmdGraph::headless(12, 4, '#' as i32);
+
+    // Combining accents, wide CJK characters and emoji sequences
+    screen.draw_text(0, 0, "Cafe\u{301} déjà");
+    screen.draw_text(0, 1, "漢字かな");
+    screen.draw_text(0, 2, "👍🏽 🇪🇸 👨‍👩‍👧");
+    // A wide glyph that would cross the right edge is left out
+    screen.draw_text(7, 3, "ab漢字");
+
+    check(screen, "draw_text_unicode");
+}
+
+#[test]
+fn overwriting_half_of_a_wide_glyph() {
+    let mut screen = BsCmdGraph::headless(6, 1, '#' as i32);
+
+    screen.put_pixel(0, 0, Cell::grapheme("漢"));
+    screen.put_pixel(2, 0, Cell::grapheme("字"));
+    screen.put_pixel(3, 0, Cell::new('x'));
+    screen.cmd_draw();
+
+    assert_eq!(screen.backend().lines(), vec!["漢 x  "]);
+    assert!(screen.get_pixel(1, 0).unwrap().is_continuation());
+    assert_eq!(screen.get_pixel(2, 0).unwrap().glyph, " ");
+}
+
+#[test]
+fn draw_img() {
+    let mut screen = BsCmdGraph::headless(10, 5, '#' as i32);
+
+    let colors = [Color::Green, Color::Yellow, Color::Indexed(208)];
+    let mut sprite = Sprite::new(3, 3);
+    for (y, color) in colors.into_iter().enumerate() {
+        for x in 0..3 {
+            sprite.set(x, y as i32, screen.texture_cell(color).bold());
+        }
+    }
+    screen.draw_img(&sprite, 1, 1);
+    screen.draw_img(&sprite, 8, 3);
+
+    check(screen, "draw_img");
+}
+
+#[test]
+fn cmd_clear_waits_for_cmd_draw() {
+    let mut screen = BsCmdGraph::headless(5, 2, '#' as i32);
+
+    screen.put_pixel(1, 1, Cell::new('@'));
+    screen.cmd_draw();
+
+    // Clearing the display doesn't reach the backend until the next frame
+    screen.cmd_clear();
+    assert_eq!(screen.backend().lines(), vec!["     ", " @   "]);
+
+    screen.put_pixel(2, 1, Cell::new('@'));
+    screen.cmd_draw();
+    assert_eq!(screen.backend().lines(), vec!["     ", "  @  "]);
+}
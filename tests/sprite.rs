@@ -0,0 +1,52 @@
+#![allow(clippy::needless_return)]
+
+use bsgraphlib::{BsCmdGraph, BsGraphError, Cell, Sprite};
+
+// Rows of a sprite, with '.' for transparent cells
+fn rows(sprite: &Sprite) -> Vec<String> {
+    return (0..sprite.height())
+        .map(|y| (0..sprite.width()).map(|x| sprite.get(x, y).map_or(".".to_owned(), |cell| cell.glyph.clone())).collect())
+        .collect();
+}
+
+#[test]
+fn sprites_are_flipped_and_rotated() {
+    let sprite = Sprite::from_text("ab.\nc.d", '.');
+
+    assert_eq!(rows(&sprite.clone().flip_horizontal()), vec![".ba", "d.c"]);
+    assert_eq!(rows(&sprite.clone().flip_vertical()), vec!["c.d", "ab."]);
+    assert_eq!(rows(&sprite.clone().rotate(1)), vec!["ca", ".b", "d."]);
+    assert_eq!(rows(&sprite.clone().rotate(2)), vec!["d.c", ".ba"]);
+    assert_eq!(rows(&sprite.clone().rotate(-1)), vec![".d", "b.", "ac"]);
+    assert_eq!(sprite.clone().rotate(4), sprite);
+}
+
+#[test]
+fn transparency_comes_from_a_key_or_a_mask() {
+    let cells = vec![Cell::new('x'), Cell::new('o'), Cell::new('x'), Cell::new('o')];
+
+    let keyed = Sprite::from_cells(2, 2, cells.clone()).unwrap().with_key(&Cell::new('o'));
+    assert_eq!(rows(&keyed), vec!["x.", "x."]);
+
+    let masked = Sprite::from_cells(2, 2, cells).unwrap().with_mask(vec![false, true, true, false]).unwrap();
+    assert_eq!(rows(&masked), vec![".o", "x."]);
+
+    assert!(matches!(
+        Sprite::from_cells(2, 2, vec![Cell::new('x')]),
+        Err(BsGraphError::SizeMismatch { expected: 4, actual: 1 })
+    ));
+}
+
+#[test]
+fn sprites_are_clipped_per_cell() {
+    let mut screen = BsCmdGraph::headless(4, 3, '#' as i32);
+    let sprite = Sprite::from_text("123\n4 6\n789", ' ');
+
+    // Partly above and to the left of the display
+    assert!(matches!(screen.try_draw_img(&sprite, -1, -1), Err(BsGraphError::OutOfBounds { x: -1, y: -1 })));
+    // Partly below and to the right of it
+    assert!(!screen.draw_img(&sprite, 2, 1));
+    screen.cmd_draw();
+
+    assert_eq!(screen.backend().lines(), vec![" 6  ", "8912", "  4 "]);
+}